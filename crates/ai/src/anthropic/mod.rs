@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{ChatPayload, ChatResponse, Choice, Message, ModelList, Role},
+    provider::ChatProvider,
+    stream::{sse_tokens, TokenStream},
+};
+
+pub const BASE_URL: &str = "https://api.anthropic.com/v1";
+pub const ENV_NAME: &str = "ANTHROPIC_API_KEY";
+pub const API_VERSION: &str = "2023-06-01";
+
+/// Anthropic requires an explicit upper bound on the generated tokens
+const MAX_TOKENS: usize = 1024;
+
+pub fn get_apikey_from_env() -> Option<String> {
+    std::env::var(ENV_NAME).ok()
+}
+
+#[derive(Debug)]
+pub struct Client {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, api_key: &str) -> Result<Client> {
+        let mut api_key = HeaderValue::from_str(api_key.trim()).map_err(|_| {
+            eyre!("Invalid api key: it contains characters not allowed in a header")
+        })?;
+        api_key.set_sensitive(true);
+
+        let client = reqwest::Client::builder()
+            .default_headers({
+                let mut headers = HeaderMap::new();
+                headers.insert(ACCEPT, "application/json".parse().unwrap());
+                headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+                headers.insert("x-api-key", api_key);
+                headers.insert("anthropic-version", API_VERSION.parse().unwrap());
+
+                headers
+            })
+            .build()?;
+
+        Ok(Client {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct MessagesPayload {
+    pub model: String,
+    pub max_tokens: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<Message>,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+}
+
+impl From<ChatPayload> for MessagesPayload {
+    /// System messages are sent through the top-level `system` field
+    fn from(payload: ChatPayload) -> Self {
+        let (system, messages): (Vec<Message>, Vec<Message>) = payload
+            .messages
+            .into_iter()
+            .partition(|m| matches!(m.role, Role::System));
+
+        let system = system
+            .into_iter()
+            .map(|m| m.content)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Self {
+            model: payload.model,
+            max_tokens: MAX_TOKENS,
+            system: (!system.is_empty()).then_some(system),
+            messages,
+            stream: payload.stream,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct MessagesResponse {
+    pub id: String,
+    pub model: String,
+    pub content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ContentBlock {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub text: Option<String>,
+}
+
+impl From<MessagesResponse> for ChatResponse {
+    fn from(response: MessagesResponse) -> Self {
+        let content = response
+            .content
+            .into_iter()
+            .filter(|b| b.block_type == "text")
+            .filter_map(|b| b.text)
+            .collect::<String>();
+
+        Self {
+            id: response.id,
+            model: response.model,
+            choices: vec![Choice {
+                index: 0,
+                message: Message::assisstant(content),
+            }],
+        }
+    }
+}
+
+/// Only `content_block_delta` events carry generated text
+#[derive(Debug, Clone, Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    delta: Option<StreamDelta>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StreamDelta {
+    text: Option<String>,
+}
+
+fn extract_token(data: &str) -> Result<Option<String>> {
+    let event = serde_json::from_str::<StreamEvent>(data)?;
+
+    if event.event_type == "error" {
+        return Err(eyre!("AI stream failed: {data}"));
+    }
+
+    Ok(event.delta.and_then(|d| d.text))
+}
+
+impl Client {
+    async fn send(&self, payload: ChatPayload) -> Result<reqwest::Response> {
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .json(&MessagesPayload::from(payload))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(eyre!("AI request failed ({status}): {body}"));
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl ChatProvider for Client {
+    async fn chat(&self, payload: ChatPayload) -> Result<ChatResponse> {
+        let response = self.send(payload).await?;
+
+        Ok(response.json::<MessagesResponse>().await?.into())
+    }
+
+    async fn models(&self) -> Result<Vec<String>> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(eyre!("Unable to list models ({status}): {body}"));
+        }
+
+        Ok(response.json::<ModelList>().await?.into())
+    }
+
+    async fn chat_stream(&self, payload: ChatPayload) -> Result<TokenStream> {
+        let payload = ChatPayload {
+            stream: true,
+            ..payload
+        };
+        let response = self.send(payload).await?;
+
+        Ok(sse_tokens(response, extract_token))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn system_messages_are_moved_to_system_field() {
+        let payload = ChatPayload::new(
+            "claude",
+            vec![
+                Message::system("be concise".into()),
+                Message::user("hello".into()),
+            ],
+        );
+
+        let payload = MessagesPayload::from(payload);
+
+        assert_eq!(payload.system, Some("be concise".into()));
+        assert_eq!(payload.messages.len(), 1);
+        assert!(matches!(payload.messages[0].role, Role::User));
+    }
+
+    #[test]
+    fn extract_text_delta() {
+        let data = r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"fix"}}"#;
+        assert_eq!(extract_token(data).unwrap(), Some("fix".into()));
+
+        let data = r#"{"type":"message_stop"}"#;
+        assert_eq!(extract_token(data).unwrap(), None);
+    }
+}
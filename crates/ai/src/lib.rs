@@ -1,2 +1,11 @@
+pub mod anthropic;
 pub mod groq;
+pub mod models;
+pub mod openai;
+pub mod provider;
+pub mod stream;
+pub mod structured;
 
+pub use provider::{ChatProvider, ProviderKind};
+pub use stream::TokenStream;
+pub use structured::{chat_json, chat_json_stream};
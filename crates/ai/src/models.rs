@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, strum::Display, strum::EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Role {
+    #[strum(serialize = "user")]
+    User,
+
+    #[strum(serialize = "system")]
+    System,
+
+    #[strum(serialize = "assistant")]
+    Assistant,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new(role: Role, content: String) -> Self {
+        Self { role, content }
+    }
+
+    pub fn role(&self) -> Role {
+        self.role.clone()
+    }
+
+    pub fn content(&self) -> String {
+        self.content.clone()
+    }
+
+    /// Create a new system message
+    pub fn system(content: String) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    /// Create a new user message
+    pub fn user(content: String) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    /// Create a new assistant message
+    pub fn assisstant(content: String) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChatPayload {
+    pub model: String,
+    pub messages: Vec<Message>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+}
+
+impl ChatPayload {
+    pub fn new(model: &str, messages: Vec<Message>) -> ChatPayload {
+        ChatPayload {
+            model: model.to_string(),
+            messages,
+            response_format: None,
+            stream: false,
+        }
+    }
+
+    /// Ask the model to only return a valid JSON object
+    pub fn json_mode(mut self) -> ChatPayload {
+        self.response_format = Some(ResponseFormat::json_object());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseFormat {
+    #[serde(rename = "type")]
+    pub format_type: String,
+}
+
+impl ResponseFormat {
+    pub fn json_object() -> Self {
+        Self {
+            format_type: "json_object".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChatResponse {
+    pub id: String,
+    pub model: String,
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Choice {
+    pub index: i32,
+    pub message: Message,
+}
+
+/// `GET /models` response, shared by OpenAI-compatible APIs and Anthropic
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ModelList {
+    pub data: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ModelInfo {
+    pub id: String,
+}
+
+impl From<ModelList> for Vec<String> {
+    fn from(list: ModelList) -> Self {
+        let mut ids = list.data.into_iter().map(|m| m.id).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+}
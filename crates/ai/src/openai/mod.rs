@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+
+use serde::Deserialize;
+
+use crate::{
+    models::{ChatPayload, ChatResponse, ModelList},
+    provider::ChatProvider,
+    stream::{sse_tokens, TokenStream},
+};
+
+pub const BASE_URL: &str = "https://api.openai.com/v1";
+pub const ENV_NAME: &str = "OPENAI_API_KEY";
+
+pub fn get_apikey_from_env() -> Option<String> {
+    std::env::var(ENV_NAME).ok()
+}
+
+struct BearerAuth(String);
+
+impl TryFrom<BearerAuth> for HeaderValue {
+    type Error = color_eyre::eyre::Error;
+
+    fn try_from(value: BearerAuth) -> Result<Self> {
+        let mut header =
+            HeaderValue::from_str(&format!("Bearer {}", value.0.trim())).map_err(|_| {
+                eyre!("Invalid api key: it contains characters not allowed in a header")
+            })?;
+        header.set_sensitive(true);
+
+        Ok(header)
+    }
+}
+
+/// Client for any OpenAI-compatible `/chat/completions` endpoint
+/// (OpenAI, Groq, Ollama, llama.cpp server, ...)
+#[derive(Debug)]
+pub struct Client {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Client {
+    /// `api_key` is optional since local servers usually don't require one
+    pub fn new(base_url: &str, api_key: Option<&str>) -> Result<Client> {
+        let client = reqwest::Client::builder()
+            .default_headers({
+                let mut headers = HeaderMap::new();
+                headers.insert(ACCEPT, "application/json".parse().unwrap());
+                headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+                if let Some(api_key) = api_key {
+                    headers.insert(
+                        AUTHORIZATION,
+                        HeaderValue::try_from(BearerAuth(api_key.to_string()))?,
+                    );
+                }
+
+                headers
+            })
+            .build()?;
+
+        Ok(Client {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+}
+
+impl Client {
+    async fn send(&self, payload: &ChatPayload) -> Result<reqwest::Response> {
+        let response = self
+            .client
+            .post(self.url("chat/completions"))
+            .json(payload)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(eyre!("AI request failed ({status}): {body}"));
+        }
+
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkResponse {
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    delta: Delta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+fn extract_token(data: &str) -> Result<Option<String>> {
+    let chunk = serde_json::from_str::<ChunkResponse>(data)?;
+
+    Ok(chunk
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|c| c.delta.content))
+}
+
+#[async_trait]
+impl ChatProvider for Client {
+    async fn chat(&self, payload: ChatPayload) -> Result<ChatResponse> {
+        let response = self.send(&payload).await?;
+
+        Ok(response.json::<ChatResponse>().await?)
+    }
+
+    async fn models(&self) -> Result<Vec<String>> {
+        let response = self.client.get(self.url("models")).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(eyre!("Unable to list models ({status}): {body}"));
+        }
+
+        Ok(response.json::<ModelList>().await?.into())
+    }
+
+    async fn chat_stream(&self, payload: ChatPayload) -> Result<TokenStream> {
+        let payload = ChatPayload {
+            stream: true,
+            ..payload
+        };
+        let response = self.send(&payload).await?;
+
+        Ok(sse_tokens(response, extract_token))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_delta_content() {
+        let data = r#"{"id":"1","choices":[{"index":0,"delta":{"content":"feat"}}]}"#;
+        assert_eq!(extract_token(data).unwrap(), Some("feat".into()));
+
+        let data = r#"{"id":"1","choices":[{"index":0,"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(extract_token(data).unwrap(), None);
+    }
+}
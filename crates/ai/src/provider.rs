@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    anthropic, groq,
+    models::{ChatPayload, ChatResponse},
+    openai,
+    stream::TokenStream,
+};
+
+/// Common interface implemented by every supported AI backend
+#[async_trait]
+pub trait ChatProvider: Send + Sync {
+    async fn chat(&self, payload: ChatPayload) -> Result<ChatResponse>;
+
+    /// Ids of the models available with the current api key
+    async fn models(&self) -> Result<Vec<String>>;
+
+    /// Stream the response tokens as they are generated
+    ///
+    /// Falls back to a single chunk for providers without streaming support
+    async fn chat_stream(&self, payload: ChatPayload) -> Result<TokenStream> {
+        let response = self.chat(payload).await?;
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .unwrap_or_default();
+
+        Ok(Box::pin(futures::stream::once(async { Ok(content) })))
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ProviderKind {
+    #[default]
+    Groq,
+
+    /// Any OpenAI-compatible endpoint (OpenAI, Ollama, llama.cpp, ...)
+    #[strum(serialize = "openai")]
+    #[serde(rename = "openai")]
+    OpenAi,
+
+    Anthropic,
+}
+
+impl ProviderKind {
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ProviderKind::Groq => groq::BASE_URL,
+            ProviderKind::OpenAi => openai::BASE_URL,
+            ProviderKind::Anthropic => anthropic::BASE_URL,
+        }
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            ProviderKind::Groq => groq::DEFAULT_MODEL,
+            ProviderKind::OpenAi => "gpt-4o-mini",
+            ProviderKind::Anthropic => "claude-3-5-haiku-latest",
+        }
+    }
+
+    /// Environment variable holding the api key for this provider
+    pub fn env_name(&self) -> &'static str {
+        match self {
+            ProviderKind::Groq => groq::models::ENV_NAME,
+            ProviderKind::OpenAi => openai::ENV_NAME,
+            ProviderKind::Anthropic => anthropic::ENV_NAME,
+        }
+    }
+
+    pub fn get_apikey_from_env(&self) -> Option<String> {
+        std::env::var(self.env_name()).ok()
+    }
+
+    /// Local OpenAI-compatible servers usually don't need a key
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self, ProviderKind::OpenAi)
+    }
+}
+
+/// Build the chat client for the given provider
+///
+/// `base_url` overrides the provider default, e.g. to point the
+/// OpenAI-compatible client to a local server
+pub fn new(
+    kind: ProviderKind,
+    api_key: Option<&str>,
+    base_url: Option<&str>,
+) -> Result<Box<dyn ChatProvider>> {
+    let base_url = base_url.unwrap_or(kind.default_base_url());
+
+    if kind.requires_api_key() && api_key.is_none() {
+        return Err(eyre!(
+            "Missing api key for {kind}, set it with `tpaws config reset` or {}",
+            kind.env_name()
+        ));
+    }
+
+    Ok(match kind {
+        ProviderKind::Groq | ProviderKind::OpenAi => {
+            Box::new(openai::Client::new(base_url, api_key)?)
+        }
+        ProviderKind::Anthropic => Box::new(anthropic::Client::new(
+            base_url,
+            api_key.unwrap_or_default(),
+        )?),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn parse_provider_kind() {
+        assert_eq!(
+            ProviderKind::from_str("openai").unwrap(),
+            ProviderKind::OpenAi
+        );
+        assert_eq!(ProviderKind::OpenAi.to_string(), "openai");
+        assert_eq!(
+            ProviderKind::from_str("anthropic").unwrap(),
+            ProviderKind::Anthropic
+        );
+        assert!(new(ProviderKind::Anthropic, None, None).is_err());
+        assert!(new(
+            ProviderKind::OpenAi,
+            None,
+            Some("http://localhost:11434/v1")
+        )
+        .is_ok());
+        assert!(new(ProviderKind::OpenAi, Some(" key\n"), None).is_ok());
+        assert!(new(ProviderKind::Anthropic, Some("k\ney"), None).is_err());
+    }
+}
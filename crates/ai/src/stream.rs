@@ -0,0 +1,46 @@
+use std::pin::Pin;
+
+use color_eyre::eyre::{eyre, Result};
+use eventsource_stream::Eventsource;
+use futures::{Stream, StreamExt};
+
+/// Tokens of a completion, in the order they are generated
+pub type TokenStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Turn a server-sent events response into a stream of tokens
+///
+/// `extract` maps the data of each event to the token it carries, if any.
+/// The stream ends on the OpenAI `[DONE]` sentinel or when the connection closes
+pub(crate) fn sse_tokens(
+    response: reqwest::Response,
+    extract: fn(&str) -> Result<Option<String>>,
+) -> TokenStream {
+    let stream = response
+        .bytes_stream()
+        .eventsource()
+        .take_while(|event| {
+            let done = matches!(event, Ok(e) if e.data.trim() == "[DONE]");
+            futures::future::ready(!done)
+        })
+        .filter_map(move |event| async move {
+            match event {
+                Ok(event) => extract(&event.data).transpose(),
+                Err(e) => Some(Err(eyre!("Invalid AI stream: {e}"))),
+            }
+        });
+
+    Box::pin(stream)
+}
+
+/// Consume the stream, calling `on_token` for each token, and return the full text
+pub async fn collect(mut stream: TokenStream, mut on_token: impl FnMut(&str)) -> Result<String> {
+    let mut content = String::new();
+
+    while let Some(token) = stream.next().await {
+        let token = token?;
+        on_token(&token);
+        content.push_str(&token);
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,175 @@
+use color_eyre::eyre::{eyre, OptionExt, Result};
+use jsonschema::JSONSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    models::{ChatPayload, Message},
+    provider::ChatProvider,
+    stream,
+};
+
+/// How many times the model is asked to fix an invalid response
+pub const MAX_RETRIES: usize = 2;
+
+/// Extract the JSON object from a model response, dropping code fences
+/// and any prose around it
+pub fn extract_json(content: &str) -> &str {
+    let content = content.trim();
+
+    if let Some(start) = content.find("```") {
+        let fenced = &content[start + 3..];
+        // skip the language tag, e.g. ```json
+        let fenced = fenced.split_once('\n').map(|(_, s)| s).unwrap_or(fenced);
+
+        if let Some(end) = fenced.find("```") {
+            return fenced[..end].trim();
+        }
+    }
+
+    match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => content,
+    }
+}
+
+pub fn schema<T: JsonSchema>() -> Value {
+    serde_json::to_value(schema_for!(T)).unwrap_or_default()
+}
+
+/// Parse and validate a model response against the schema of `T`
+///
+/// The error is meant to be fed back to the model
+pub fn parse<T>(content: &str) -> std::result::Result<T, String>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let value = serde_json::from_str::<Value>(extract_json(content))
+        .map_err(|e| format!("The response is not valid JSON: {e}"))?;
+
+    let schema = schema::<T>();
+    let compiled = JSONSchema::compile(&schema).map_err(|e| e.to_string())?;
+
+    if let Err(errors) = compiled.validate(&value) {
+        let errors = errors
+            .map(|e| format!("- {} (at `{}`)", e, e.instance_path))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        return Err(format!("The JSON does not match the schema:\n{errors}"));
+    }
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Send `payload` in JSON mode and parse the response into `T`,
+/// retrying with the validation error when the model gets it wrong
+pub async fn chat_json<T>(provider: &dyn ChatProvider, payload: ChatPayload) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    request_json(provider, payload, None).await
+}
+
+/// Same as [`chat_json`] but streams the response, calling `on_token`
+/// for each generated token
+pub async fn chat_json_stream<T>(
+    provider: &dyn ChatProvider,
+    payload: ChatPayload,
+    mut on_token: impl FnMut(&str),
+) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    request_json(provider, payload, Some(&mut on_token)).await
+}
+
+async fn request_json<T>(
+    provider: &dyn ChatProvider,
+    payload: ChatPayload,
+    mut on_token: Option<&mut dyn FnMut(&str)>,
+) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let mut payload = payload.json_mode();
+    payload.messages.push(Message::system(format!(
+        "Reply only with a JSON object matching this JSON schema:\n{}",
+        serde_json::to_string(&schema::<T>())?
+    )));
+
+    let mut last_error = String::new();
+
+    for _ in 0..=MAX_RETRIES {
+        let content = match on_token.as_mut() {
+            Some(on_token) => {
+                let stream = provider.chat_stream(payload.clone()).await?;
+                stream::collect(stream, |t| on_token(t)).await?
+            }
+            None => {
+                provider
+                    .chat(payload.clone())
+                    .await?
+                    .choices
+                    .into_iter()
+                    .next()
+                    .ok_or_eyre("Invalid AI response. No choices returned.")?
+                    .message
+                    .content
+            }
+        };
+
+        match parse::<T>(&content) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                payload.messages.push(Message::assisstant(content));
+                payload.messages.push(Message::user(format!(
+                    "{e}\nFix the response and reply only with the JSON object."
+                )));
+                last_error = e;
+            }
+        }
+    }
+
+    Err(eyre!(
+        "Invalid AI response after {} attempts.\n{last_error}",
+        MAX_RETRIES + 1
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct Commit {
+        message: String,
+        description: Option<String>,
+    }
+
+    #[test]
+    fn extract_json_from_fences_and_prose() {
+        let fenced = "Here you go:\n```json\n{\"message\": \"feat\"}\n```\nEnjoy";
+        assert_eq!(extract_json(fenced), "{\"message\": \"feat\"}");
+
+        let prose = "Sure! {\"message\": \"feat\"} hope it helps";
+        assert_eq!(extract_json(prose), "{\"message\": \"feat\"}");
+
+        assert_eq!(extract_json(" {\"a\": 1} "), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn parse_validates_schema() {
+        let commit = parse::<Commit>("```\n{\"message\": \"feat(1): add\"}\n```").unwrap();
+        assert_eq!(commit.message, "feat(1): add");
+        assert!(commit.description.is_none());
+
+        let error = parse::<Commit>("{\"description\": \"missing\"}").unwrap_err();
+        assert!(error.contains("message"));
+
+        assert!(parse::<Commit>("not json").is_err());
+    }
+}
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 use commands::aws::PullRequestStatus;
+use config::prompts::PromptKind;
+use target_process::changelog::ChangelogFormat;
 
 #[derive(Parser, Debug, Clone)]
 #[command(about, long_about = None)]
@@ -20,20 +24,32 @@ pub struct Args {
     #[arg(long, global = true)]
     pub debug: bool,
 
+    /// git remote of the repository (defaults to `origin`)
+    #[arg(long, global = true)]
+    pub remote: Option<String>,
+
+    /// named profile of the global config to use, instead of the one matching the remote
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile_set: Option<String>,
+
     /// print current version
     #[arg(long, short = 'v')]
     pub version: bool,
 }
 
 #[derive(Debug, Clone, strum::Display, strum::EnumString)]
-pub enum ReleasePushTarget {
-    Staging,
-    Prod,
-    All,
+#[strum(serialize_all = "kebab-case")]
+pub enum ChangelogSource {
+    /// TargetProcess releases named `<prefix>@<version>`
+    TargetProcess,
+
+    /// Git tags named `<prefix>@<version>` (or `v<version>` without prefix)
+    Git,
 }
 
 #[derive(Subcommand, strum::Display, Debug, Clone)]
 pub enum ReleaseCommands {
+    /// Start a `git flow` release and create the matching TargetProcess release
     Start {
         #[arg(long)]
         patch: bool,
@@ -43,20 +59,74 @@ pub enum ReleaseCommands {
 
         #[arg(long)]
         major: bool,
+
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Do not create the TargetProcess release
+        #[arg(long)]
+        no_tp: bool,
     },
-    Push {
-        #[arg(long, short, default_value = "All")]
-        target: ReleasePushTarget,
+    /// Post the changelog of a release to the Slack release channel
+    Announce {
+        /// e.g. `1.4.0`
+        version: String,
+
+        #[arg(long, short = 'P')]
+        project: Option<String>,
 
-        /// pipeline to trigger
+        /// pipeline linked from the announcement
         #[arg(long, short = 'n')]
         pipeline_name: Option<String>,
 
-        /// aws profile
-        #[arg(long, default_value = "default")]
-        profile: String,
+        /// aws profile (defaults to `default`)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum TpReleaseCommands {
+    /// Create a TargetProcess release
+    Create {
+        /// Release name, e.g. `web@1.4.0`
+        name: String,
+
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Start date (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        start: Option<chrono::NaiveDate>,
+
+        /// End date (YYYY-MM-DD), defaults to two weeks after the start
+        #[arg(long)]
+        end: Option<chrono::NaiveDate>,
+    },
+    /// Assign tickets to a TargetProcess release
+    Add {
+        /// userStory IDs or URLs
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Release name (defaults to the current `release/*` branch)
+        #[arg(long, short)]
+        release: Option<String>,
+
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+    },
+    /// List the tickets of a TargetProcess release
+    Show {
+        /// Release name, e.g. `web@1.4.0`
+        name: String,
+
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        #[arg(long)]
+        json: bool,
     },
-    Finish,
 }
 
 #[derive(Subcommand, strum::Display, Debug, Clone)]
@@ -78,6 +148,37 @@ pub enum TicketCommands {
         #[arg(long)]
         json: bool,
     },
+    /// Show the Epic → Feature → UserStory → Tasks/Bugs hierarchy of a ticket
+    Tree {
+        /// userStory ID or URL
+        id_or_url: Option<String>,
+
+        /// Print the tree as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the files attached to a ticket
+    #[command(args_conflicts_with_subcommands = true)]
+    Attachments {
+        /// userStory ID or URL
+        id_or_url: Option<String>,
+
+        /// Print the attachments as json
+        #[arg(long)]
+        json: bool,
+
+        #[command(subcommand)]
+        subcommands: Option<AttachmentsCommands>,
+    },
+    /// Upload a file (e.g. a screenshot or a log) to a ticket
+    Attach {
+        /// File to upload
+        file: PathBuf,
+
+        /// userStory ID or URL (defaults to the current branch)
+        #[arg(long, short)]
+        id: Option<String>,
+    },
     /// Run `git flow start` and update status/assigned developer
     Start {
         /// userStory ID or URL
@@ -116,26 +217,85 @@ pub enum TicketCommands {
 
         #[arg(long, default_value_t = false)]
         title_only: bool,
+
+        /// Send the staged changes (`git diff --cached`) as context
+        #[arg(long, default_value_t = false)]
+        diff: bool,
+
+        /// Run `git commit` with the generated message after confirmation
+        #[arg(long, default_value_t = false)]
+        commit: bool,
+
+        /// Override the configured AI model, see `tpaws ai models`
+        #[arg(long)]
+        model: Option<String>,
     },
 
     /// Generate a changelog from a targetprocess release
     #[clap(alias = "changelog")]
     GenerateChangelog {
-        from: usize,
+        /// First version to include, e.g. `1.4.0` or `1.4`
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last version to include, e.g. `2.1.3` or `2`
+        #[arg(long)]
+        to: Option<String>,
 
-        to: Option<usize>,
+        /// Build the changelog from the commits between two refs (`<tagA>..<tagB>`)
+        /// instead of TargetProcess releases
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        from_git: Option<String>,
 
         #[arg(long, short = 'P')]
-        project: String,
+        project: Option<String>,
 
         #[arg(long, short = 'p', default_value_t = String::new())]
         prefix: String,
 
+        /// Where to discover the released versions
+        #[arg(long, default_value = "target-process")]
+        source: ChangelogSource,
+
+        /// markdown, plain, keep-a-changelog, json, html or slack
+        #[arg(long, short, default_value = "markdown")]
+        format: ChangelogFormat,
+
+        /// Same as `--format plain`
         #[arg(long, default_value_t = false)]
         plain: bool,
 
         #[arg(long, default_value_t = false)]
         no_title: bool,
+
+        /// Write the changelog to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Insert the new versions at the top of `--output`, skipping the ones already present
+        #[arg(long, requires = "output")]
+        prepend: bool,
+    },
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum AttachmentsCommands {
+    /// Save the attachments of a ticket locally
+    Download {
+        /// userStory ID or URL
+        id_or_url: Option<String>,
+
+        /// Destination directory
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Only download the attachment with this name
+        #[arg(long, short)]
+        name: Option<String>,
+
+        /// Replace the files that already exist
+        #[arg(long, short)]
+        force: bool,
     },
 }
 
@@ -143,7 +303,7 @@ pub enum TicketCommands {
 pub enum PullRequestCommands {
     /// Create a PR
     Create {
-        /// Do not notify slack channel
+        /// Notify the configured Slack channel, see `tpaws slack setup`
         #[arg(long)]
         slack: bool,
 
@@ -158,9 +318,9 @@ pub enum PullRequestCommands {
         #[arg(long, short)]
         description: Option<String>,
 
-        /// PR base branch
-        #[arg(long, short, default_value = "develop")]
-        base: String,
+        /// PR base branch (defaults to `develop`)
+        #[arg(long, short)]
+        base: Option<String>,
 
         #[arg(long, default_value_t = false)]
         ai: bool,
@@ -192,11 +352,155 @@ pub enum PullRequestCommands {
         #[arg(long, short)]
         status: Option<PullRequestStatus>,
     },
+
+    /// Approve a PR (defaults to the PR of the current branch)
+    Approve {
+        /// PR id
+        id: Option<String>,
+    },
+
+    /// Squash merge a PR (defaults to the PR of the current branch)
+    Merge {
+        /// PR id
+        id: Option<String>,
+    },
+
+    /// Close a PR without merging (defaults to the PR of the current branch)
+    Close {
+        /// PR id
+        id: Option<String>,
+    },
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum PromptsCommands {
+    /// List prompt templates and where they are loaded from
+    List,
+
+    /// Edit a prompt template with $EDITOR
+    Edit { name: PromptKind },
+
+    /// Restore the default template (all of them if no name is given)
+    Reset { name: Option<PromptKind> },
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum AiCommands {
+    /// List the models available for the configured provider
+    Models {
+        /// Ignore the cached listing
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Manage the prompt templates used by `generate-commit` and `pr create --ai`
+    Prompts {
+        #[command(subcommand)]
+        subcommands: PromptsCommands,
+    },
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum ReviewersCommands {
+    /// Add a reviewer (or rename an existing one)
+    Add {
+        name: String,
+
+        /// Slack member ID, e.g. U067RQQ94NM
+        id: String,
+
+        /// Store in the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Remove a reviewer by name or Slack ID
+    Remove {
+        name_or_id: String,
+
+        /// Remove from the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// List global and project reviewers
+    List,
+}
+
+#[derive(Subcommand, strum::Display, Debug, Clone)]
+pub enum SlackCommands {
+    /// Configure the webhook, channel and your Slack member ID
+    Setup {
+        /// Store in the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Manage the reviewers offered by `pr create --slack`
+    Reviewers {
+        #[command(subcommand)]
+        subcommands: ReviewersCommands,
+    },
 }
 
 #[derive(Subcommand, strum::Display, Debug, Clone)]
 pub enum ConfigCommands {
     Reset,
+
+    /// Print the settings in use
+    Show {
+        /// Explain where each value comes from (flag, env, tpaws.json, global config, default)
+        #[arg(long)]
+        origin: bool,
+    },
+
+    /// Print a config value, e.g. `base_branch` or `slack.channel`
+    Get {
+        key: String,
+
+        /// Read from the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Update a config value, JSON values (`true`, `3`, `null`) are accepted
+    Set {
+        key: String,
+        value: String,
+
+        /// Write to the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Open the config in $EDITOR, saved only when valid
+    Edit {
+        /// Edit the project tpaws.json instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Print the location of the config file
+    Path {
+        /// Location of the project tpaws.json
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Check the TargetProcess token, the AWS profile and the AI api key
+    Validate {
+        /// aws profile (defaults to `default`)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+
+    /// List the named profiles, set them with `tpaws config set profiles.<name>.<key>`
+    Profiles,
+
+    /// Profile used in the repositories no profile `remotes` match, the global settings without a name
+    Use {
+        name: Option<String>,
+    },
 }
 
 #[derive(Subcommand, strum::Display, Debug, Clone)]
@@ -208,21 +512,54 @@ pub enum Commands {
         subcommands: ConfigCommands,
     },
 
+    /// Manage AI settings
+    Ai {
+        #[command(subcommand)]
+        subcommands: AiCommands,
+    },
+
     /// Manage target process
     #[clap(visible_alias = "us")]
     Ticket {
         #[command(subcommand)]
         subcommands: TicketCommands,
     },
+    /// Release
+    Release {
+        #[command(subcommand)]
+        subcommands: ReleaseCommands,
+    },
+
+    /// Manage TargetProcess releases
+    TpRelease {
+        #[command(subcommand)]
+        subcommands: TpReleaseCommands,
+    },
+
+    /// Manage Slack notifications
+    Slack {
+        #[command(subcommand)]
+        subcommands: SlackCommands,
+    },
+
+    /// Overview of the current iteration
+    Sprint {
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Print the iteration as json
+        #[arg(long)]
+        json: bool,
+    },
     /// Create / Manage and List pull requests
     #[clap(visible_alias = "pr")]
     PullRequest {
         #[command(subcommand)]
         subcommands: PullRequestCommands,
 
-        /// aws profile
-        #[arg(long, default_value = "default")]
-        profile: String,
+        /// aws profile (defaults to `default`)
+        #[arg(long)]
+        profile: Option<String>,
     },
 
     Init {
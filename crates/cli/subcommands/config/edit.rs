@@ -0,0 +1,72 @@
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use config::{Config, ProjectConfig};
+use inquire::Confirm;
+
+use crate::utils;
+
+/// Open the global config (or `tpaws.json`) in `$EDITOR`
+///
+/// Changes are made on a copy and saved only when they parse
+pub async fn edit(project: bool, dry_run: bool) -> Result<()> {
+    let path = match project {
+        true => ProjectConfig::path()?,
+        false => Config::path()?,
+    };
+
+    if dry_run {
+        println!("Would edit {}", path.display());
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(eyre!("{} does not exist", path.display()));
+    }
+
+    // The global config holds secrets: the draft is only readable by the user and
+    // removed when dropped, whatever happens in the editor
+    let draft = tempfile::Builder::new()
+        .prefix("tpaws-")
+        .suffix(".json")
+        .tempfile()?;
+    let original = std::fs::read_to_string(&path)?;
+    std::fs::write(draft.path(), &original)?;
+
+    let result = loop {
+        utils::open_in_editor(draft.path()).await?;
+
+        let contents = std::fs::read_to_string(draft.path())?;
+        let parsed = match project {
+            true => serde_json::from_str::<ProjectConfig>(&contents).map(|_| ()),
+            false => serde_json::from_str::<Config>(&contents).map(|_| ()),
+        };
+
+        match parsed {
+            Ok(()) => break Some(contents),
+            Err(e) => {
+                println!("{}", format!("Invalid config: {e}").red());
+
+                if !Confirm::new("Edit again?").with_default(true).prompt()? {
+                    break None;
+                }
+            }
+        }
+    };
+
+    draft.close()?;
+
+    match result {
+        Some(contents) => {
+            std::fs::write(&path, &contents)?;
+
+            if let (false, Ok(previous)) = (project, serde_json::from_str::<Config>(&original)) {
+                serde_json::from_str::<Config>(&contents)?.forget_removed_profiles(&previous)?;
+            }
+
+            println!("Saved {}", path.display().to_string().green());
+        }
+        None => println!("Changes discarded."),
+    }
+
+    Ok(())
+}
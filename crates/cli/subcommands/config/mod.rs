@@ -1,34 +1,54 @@
-use ai::groq;
+mod edit;
+mod profiles;
+mod show;
+mod validate;
+mod values;
+
+pub use edit::edit;
+pub use profiles::{profiles, use_profile};
+pub use show::show;
+pub use validate::validate;
+pub use values::{get, path, set};
+
+use ai::ProviderKind;
 use color_eyre::{eyre::eyre, Result};
-use colored::*;
 use commands::git;
-use config::{util::inject_env, Config, DEFAULT_AI_MODEL};
-use inquire::Text;
+use config::Config;
+use inquire::{Select, Text};
+use strum::IntoEnumIterator;
+use target_process::TargetProcessClient;
 
 pub async fn reset() -> Result<()> {
-    if !target_process::is_configured() {
-        let prompt = "Invalid configuration detected. Do you want to fix this now?".to_string();
-
-        let fix = inquire::Confirm::new(&prompt).with_default(true).prompt()?;
-
-        if !fix {
-            return Err(eyre!(
-                "Please make sure to have the correct configuration before continuing.\nMissing env `{}`",
-                target_process::ENV_NAME.yellow()
-            ));
-        }
-
-        let tpurl = inquire::Text::new("Target Process base url:")
-            .with_help_message("e.g https://my-company.tpondemand.com")
-            .prompt()?;
+    let previous = Config::read().await.ok();
+    let current = previous
+        .as_ref()
+        .and_then(|c| crate::utils::target_process_client(None, c).ok().flatten());
+
+    let tp_url = Text::new("Target Process base url:")
+        .with_help_message("e.g https://my-company.tpondemand.com")
+        .with_default(
+            &current
+                .as_ref()
+                .map(|c| c.base_url().to_string())
+                .or_else(|| previous.as_ref().and_then(|c| c.tp_url.clone()))
+                .unwrap_or_default(),
+        )
+        .prompt()?;
 
-        inject_env(target_process::ENV_NAME, &tpurl).await?;
-    }
+    let tp_apikey = Text::new("Target Process access token:")
+        .with_help_message("stored in the OS keyring (or an encrypted file)")
+        .with_default(
+            &current
+                .as_ref()
+                .map(|c| c.token().to_string())
+                .or_else(|| previous.as_ref().and_then(|c| c.tp_apikey.clone()))
+                .unwrap_or_default(),
+        )
+        .prompt()?;
 
-    let me = match target_process::get_me().await {
-        Ok(me) => me,
-        Err(e) => panic!("Unable to retrive current user: {:?}", e),
-    };
+    let me = target_process::get_me(&TargetProcessClient::new(&tp_url, &tp_apikey))
+        .await
+        .map_err(|e| eyre!("Unable to retrive current user: {e}"))?;
 
     let name = git::config("user.name".to_string())
         .await
@@ -58,23 +78,33 @@ pub async fn reset() -> Result<()> {
         .with_default(&potential_username.unwrap_or(me.login))
         .prompt()?;
 
-    let groq_api_key = Text::new("Groq API Key:")
-        .with_help_message("Get your API key from https://groq.dev")
-        .with_default(&groq::models::get_apikey_from_env().unwrap_or_default())
-        .prompt_skippable()?;
+    let ai_provider = Select::new("AI Provider:", ProviderKind::iter().collect())
+        .with_help_message("openai works with any OpenAI-compatible endpoint (e.g. Ollama)")
+        .prompt()?;
 
-    let ai_model = Text::new("AI Model:")
-        .with_help_message("https://console.groq.com/docs/models#production-models")
-        .with_default(DEFAULT_AI_MODEL)
-        .prompt_skippable()?;
+    let ai_base_url = match ai_provider {
+        ProviderKind::OpenAi => Text::new("AI Base URL:")
+            .with_default(ai_provider.default_base_url())
+            .prompt_skippable()?,
+        _ => None,
+    };
 
-    let tp_url = Text::new("Target Process URL")
-        .with_placeholder("https://my-company.tpondemand.com")
-        .with_default(&target_process::get_base_url())
-        .prompt_skippable()?;
+    let ai_api_key = Text::new(&format!("{ai_provider} API Key:"))
+        .with_help_message(&format!(
+            "Can also be set through {}",
+            ai_provider.env_name()
+        ))
+        .with_default(&ai_provider.get_apikey_from_env().unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|k| !k.is_empty());
+
+    let (groq_api_key, ai_api_key) = match ai_provider {
+        ProviderKind::Groq => (ai_api_key, None),
+        _ => (None, ai_api_key),
+    };
 
-    let tp_apikey = Text::new("Target Process URL")
-        .with_default(&target_process::get_token().ok().unwrap_or_default())
+    let ai_model = Text::new("AI Model:")
+        .with_default(ai_provider.default_model())
         .prompt_skippable()?;
 
     let config = Config {
@@ -86,8 +116,25 @@ pub async fn reset() -> Result<()> {
         arn: None,
         groq_api_key,
         ai_model,
-        tp_url,
-        tp_apikey,
+        ai_provider: Some(ai_provider.to_string()),
+        ai_base_url,
+        ai_api_key,
+        tp_url: Some(tp_url),
+        tp_apikey: Some(tp_apikey),
+        // keep the reviewer directory and the defaults across resets
+        slack: previous.as_ref().and_then(|c| c.slack.clone()),
+        project: previous.as_ref().and_then(|c| c.project.clone()),
+        base_branch: previous.as_ref().and_then(|c| c.base_branch.clone()),
+        aws_profile: previous.as_ref().and_then(|c| c.aws_profile.clone()),
+        remote: previous.as_ref().and_then(|c| c.remote.clone()),
+        profiles: previous
+            .as_ref()
+            .map(|c| c.profiles.clone())
+            .unwrap_or_default(),
+        hooks: previous.as_ref().and_then(|c| c.hooks.clone()),
+        secrets_loaded: previous.as_ref().is_some_and(|c| c.secrets_loaded),
+        default_profile: previous.and_then(|c| c.default_profile),
+        active_profile: None,
     };
 
     config.write()
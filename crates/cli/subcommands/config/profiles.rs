@@ -0,0 +1,61 @@
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use config::Config;
+
+/// List the profiles, the selected one is highlighted
+pub async fn profiles(config: &Config) -> Result<()> {
+    if config.profiles.is_empty() {
+        println!(
+            "No profile, add one with {}",
+            "tpaws config set profiles.<name>.tp_url <url>".bold()
+        );
+        return Ok(());
+    }
+
+    let active = config.profile().map(|(name, _)| name);
+
+    for (name, profile) in &config.profiles {
+        let marker = match Some(name.as_str()) == active {
+            true => "*".green(),
+            false => " ".normal(),
+        };
+
+        let default = match config.default_profile.as_ref() == Some(name) {
+            true => " (default)".dimmed(),
+            false => "".normal(),
+        };
+
+        println!(
+            "{marker} {}{default} {}",
+            name.bold(),
+            profile.remotes.join(", ").dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Set the profile used outside of the repositories matched by a profile
+pub async fn use_profile(name: Option<String>, config: &Config, dry_run: bool) -> Result<()> {
+    if let Some(name) = &name {
+        if !config.profiles.contains_key(name) {
+            return Err(eyre!(
+                "Unknown profile `{name}`, see `tpaws config profiles`"
+            ));
+        }
+    }
+
+    let mut updated = config.clone();
+    updated.default_profile = name.clone();
+
+    if !dry_run {
+        updated.write()?;
+    }
+
+    match name {
+        Some(name) => println!("Using the {} profile", name.bold()),
+        None => println!("Using the global settings"),
+    }
+
+    Ok(())
+}
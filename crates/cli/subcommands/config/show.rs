@@ -0,0 +1,57 @@
+use color_eyre::Result;
+use colored::*;
+use config::{Config, Origin, ProjectConfig, Resolved, Setting};
+use strum::IntoEnumIterator;
+
+use crate::utils;
+
+/// Print the settings in use, `origin` tells which layer each one comes from
+pub async fn show(
+    remote: Option<String>,
+    project: Option<&ProjectConfig>,
+    config: &Config,
+    origin: bool,
+) -> Result<()> {
+    if let Some((name, _)) = config.profile() {
+        println!("{:<14} {}", "profile".bold(), name.green());
+    }
+
+    for setting in Setting::iter() {
+        let flag = match setting {
+            Setting::Remote => remote.clone(),
+            _ => None,
+        };
+
+        let resolved = setting
+            .resolve(flag, project, config)
+            .or_else(|| match setting {
+                Setting::AiModel => Some(Resolved {
+                    value: utils::get_ai_provider(config)
+                        .ok()?
+                        .default_model()
+                        .to_string(),
+                    origin: Origin::Default,
+                }),
+                _ => None,
+            });
+
+        let value = resolved.as_ref().map_or("-".dimmed(), |r| r.value.normal());
+
+        match (origin, resolved) {
+            (true, Some(resolved)) => println!(
+                "{:<14} {value} {}",
+                setting.to_string().bold(),
+                format!("({})", resolved.origin).dimmed()
+            ),
+            _ => println!("{:<14} {value}", setting.to_string().bold()),
+        }
+    }
+
+    println!(
+        "{:<14} {}",
+        "secrets".bold(),
+        config::secrets::store()?.name()
+    );
+
+    Ok(())
+}
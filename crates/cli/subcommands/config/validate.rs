@@ -0,0 +1,75 @@
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use commands::aws;
+use config::Config;
+use spinners::{Spinner, Spinners};
+use target_process::TargetProcessClient;
+
+use crate::utils;
+
+/// Print the outcome of a check, returns whether it passed
+fn report(spinner: &mut Spinner, name: &str, result: Result<String>) -> bool {
+    match result {
+        Ok(detail) => {
+            spinner.stop_and_persist("✅", format!("{name}: {}", detail.green()));
+            true
+        }
+        Err(e) => {
+            spinner.stop_and_persist("❌", format!("{name}: {}", e.to_string().red()));
+            false
+        }
+    }
+}
+
+/// Check the TargetProcess token, the AWS profile and the AI api key
+pub async fn validate(
+    tp: Option<&TargetProcessClient>,
+    profile: String,
+    config: &Config,
+) -> Result<()> {
+    let mut spinner = Spinner::new(Spinners::Dots, "Checking TargetProcess ...".into());
+    let target_process = match tp {
+        Some(tp) => target_process::get_me(tp)
+            .await
+            .map(|me| format!("logged in as {} {}", me.first_name, me.last_name))
+            .map_err(|e| eyre!("{e}")),
+        None => Err(eyre!("not configured, run `tpaws config reset`")),
+    };
+    let target_process = report(&mut spinner, "TargetProcess", target_process);
+
+    let mut spinner = Spinner::new(
+        Spinners::Dots,
+        format!("Checking AWS profile {profile} ..."),
+    );
+    let aws = aws::get_caller_identity(profile.clone())
+        .await
+        .map(|identity| format!("{profile} ({})", identity.arn))
+        .map_err(|e| eyre!("{profile}: {}", e.to_string().trim()));
+    let aws = report(&mut spinner, "AWS", aws);
+
+    let provider = utils::get_ai_provider(config)?;
+    let mut spinner = Spinner::new(Spinners::Dots, format!("Checking {provider} ..."));
+    let ai = match utils::get_ai_api_key(config, provider) {
+        None if provider.requires_api_key() => Err(eyre!("no api key configured")),
+        api_key => {
+            match ai::provider::new(
+                provider,
+                api_key.as_deref(),
+                utils::get_ai_base_url(config).as_deref(),
+            ) {
+                Ok(client) => client
+                    .models()
+                    .await
+                    .map(|models| format!("{} models available", models.len())),
+                Err(e) => Err(e),
+            }
+        }
+    };
+    let ai = report(&mut spinner, &provider.to_string(), ai);
+
+    if !(target_process && aws && ai) {
+        return Err(eyre!("Invalid configuration"));
+    }
+
+    Ok(())
+}
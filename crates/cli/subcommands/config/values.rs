@@ -0,0 +1,177 @@
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
+use colored::*;
+use config::{Config, ProjectConfig};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Value at a dotted path, e.g. `slack.channel`
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.get(part))
+}
+
+/// Set the value at a dotted path, creating the missing objects
+fn assign(root: &mut Value, key: &str, new_value: Value) -> Result<()> {
+    let mut current = root;
+
+    for part in key.split('.') {
+        if current.is_null() {
+            *current = Value::Object(Default::default());
+        }
+
+        current = current
+            .as_object_mut()
+            .ok_or_eyre(format!("`{key}` is not an object path"))?
+            .entry(part)
+            .or_insert(Value::Null);
+    }
+
+    *current = new_value;
+
+    Ok(())
+}
+
+/// Apply `key = raw` to the config, `raw` is read as JSON when the field accepts it
+/// (`true`, `3`, `null`, `[...]`) and as a string otherwise
+fn with_value<T>(config: &T, key: &str, raw: &str) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let original = serde_json::to_value(config)?;
+
+    let mut candidates = vec![Value::String(raw.to_string())];
+    if let Ok(parsed) = serde_json::from_str::<Value>(raw) {
+        candidates.insert(0, parsed);
+    }
+
+    let mut error = None;
+
+    for candidate in candidates {
+        let mut value = original.clone();
+        assign(&mut value, key, candidate)?;
+
+        match serde_json::from_value::<T>(value) {
+            // unknown fields are dropped while deserializing
+            Ok(config) if lookup(&serde_json::to_value(&config)?, key).is_none() => {
+                return Err(eyre!("Unknown key `{key}`"));
+            }
+            Ok(config) => return Ok(config),
+            Err(e) => error = Some(e),
+        }
+    }
+
+    Err(eyre!(
+        "Invalid value for `{key}`: {}",
+        error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+async fn read_project() -> Result<ProjectConfig> {
+    ProjectConfig::read()
+        .await
+        .ok_or_eyre("No tpaws.json found, run `tpaws init` first")
+}
+
+/// Print a value of the global config, or of `tpaws.json` with `project`
+pub async fn get(key: String, project: bool, config: &Config) -> Result<()> {
+    let value = match project {
+        true => serde_json::to_value(read_project().await?)?,
+        false => serde_json::to_value(config)?,
+    };
+
+    match lookup(&value, &key) {
+        None | Some(Value::Null) => return Err(eyre!("`{key}` is not set")),
+        Some(Value::String(s)) => println!("{s}"),
+        Some(v) => println!("{}", serde_json::to_string_pretty(v)?),
+    }
+
+    Ok(())
+}
+
+pub async fn set(
+    key: String,
+    value: String,
+    project: bool,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    if project {
+        if matches!(key.as_str(), "slack.webhook_url" | "slack.bot_token") {
+            return Err(eyre!(
+                "tpaws.json is shared with the repository, set `{key}` without --project"
+            ));
+        }
+
+        let updated = with_value(&read_project().await?, &key, &value)?;
+
+        if !dry_run {
+            updated.write()?;
+        }
+    } else {
+        let mut updated = with_value(config, &key, &value)?;
+        updated.secrets_loaded = config.secrets_loaded;
+
+        if !dry_run {
+            updated.write()?;
+        }
+    }
+
+    println!("{} = {}", key.bold(), value.green());
+
+    Ok(())
+}
+
+pub async fn path(project: bool) -> Result<()> {
+    let path = match project {
+        true => ProjectConfig::path()?,
+        false => Config::path()?,
+    };
+
+    println!("{}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn set_values() {
+        let config = config();
+
+        let updated = with_value(&config, "base_branch", "main").unwrap();
+        assert_eq!(updated.base_branch.as_deref(), Some("main"));
+
+        let updated = with_value(&config, "user_id", "42").unwrap();
+        assert_eq!(updated.user_id, 42);
+
+        // numbers are kept as strings for string fields
+        let updated = with_value(&config, "remote", "42").unwrap();
+        assert_eq!(updated.remote.as_deref(), Some("42"));
+
+        let updated = with_value(&config, "slack.channel", "#dev").unwrap();
+        assert_eq!(updated.slack.unwrap().channel.as_deref(), Some("#dev"));
+
+        let updated = with_value(&config, "ai_model", "gpt-4o").unwrap();
+        let updated = with_value(&updated, "ai_model", "null").unwrap();
+        assert_eq!(updated.ai_model, None);
+
+        assert!(with_value(&config, "user_id", "abc").is_err());
+        assert!(with_value(&config, "unknown", "x").is_err());
+        assert!(with_value(&config, "slack.unknown", "x").is_err());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod ai;
 pub mod config;
 pub mod pull_request;
+pub mod release;
+pub mod slack;
+pub mod sprint;
+pub mod tp_release;
 pub mod user_story;
@@ -0,0 +1,46 @@
+use color_eyre::{eyre::OptionExt, Result};
+use colored::*;
+use commands::aws;
+
+use crate::{
+    context::GlobalContext,
+    subcommands::slack::{self, PrEvent},
+};
+
+use super::resolve_pr;
+
+pub async fn approve(ctx: GlobalContext, id: Option<String>, dry_run: bool) -> Result<()> {
+    let pull_request = resolve_pr(&ctx, id).await?;
+    let revision_id = pull_request
+        .revision_id
+        .clone()
+        .ok_or_eyre("Unable to get the PR revision")?;
+
+    println!(
+        "Approving {}: {}",
+        pull_request.id,
+        pull_request.title.yellow()
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    aws::approve_pull_request(pull_request.id.clone(), revision_id, ctx.profile.clone()).await?;
+
+    // The PR is already approved, a Slack failure must not hide it
+    if let Err(e) = slack::notify_pr_event(
+        &ctx.config,
+        &ctx.repository,
+        &pull_request.id,
+        PrEvent::Approved,
+    )
+    .await
+    {
+        eprintln!("{}", format!("⚠ Slack thread not updated: {e}").yellow());
+    }
+
+    println!("{}", "Approved".green());
+
+    Ok(())
+}
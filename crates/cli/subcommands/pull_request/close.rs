@@ -0,0 +1,51 @@
+use color_eyre::Result;
+use colored::*;
+use commands::aws;
+use inquire::Confirm;
+
+use crate::{
+    context::GlobalContext,
+    subcommands::slack::{self, PrEvent},
+};
+
+use super::resolve_pr;
+
+pub async fn close(ctx: GlobalContext, id: Option<String>, dry_run: bool) -> Result<()> {
+    let pull_request = resolve_pr(&ctx, id).await?;
+
+    println!(
+        "Closing {}: {}",
+        pull_request.id,
+        pull_request.title.yellow()
+    );
+
+    if !Confirm::new("Do you confirm?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("Operation aborted.");
+        return Ok(());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    aws::close_pull_request(pull_request.id.clone(), ctx.profile.clone()).await?;
+
+    // The PR is already closed, a Slack failure must not hide it
+    if let Err(e) = slack::notify_pr_event(
+        &ctx.config,
+        &ctx.repository,
+        &pull_request.id,
+        PrEvent::Closed,
+    )
+    .await
+    {
+        eprintln!("{}", format!("⚠ Slack thread not updated: {e}").yellow());
+    }
+
+    println!("{}", "Closed".green());
+
+    Ok(())
+}
@@ -1,17 +1,28 @@
-use std::collections::HashMap;
-
-use color_eyre::eyre::OptionExt;
-use ai::groq::{ self, models };
+use ai::models::{ChatPayload, Message};
 use arboard::Clipboard;
+use color_eyre::eyre::OptionExt;
 use color_eyre::Result;
 use colored::*;
 use commands::{aws, git};
-use config::DEFAULT_AI_MODEL;
+use config::{prompts::PromptKind, Reviewer};
 use inquire::{Confirm, Select, Text};
 use spinners::{Spinner, Spinners};
 
-use crate::{cli, context::GlobalContext, costants, utils};
+use crate::{
+    cli,
+    context::GlobalContext,
+    events::{self, Event, EventKind},
+    subcommands::{self, ai::models},
+    utils,
+};
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct PullRequestContent {
+    title: String,
+    description: Option<String>,
+}
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create(
     ctx: &mut GlobalContext,
     args: cli::Args,
@@ -23,19 +34,18 @@ pub async fn create(
     ai_enhance: bool,
     ai_model: Option<String>,
 ) -> Result<()> {
-    let model = ai_model.unwrap_or(DEFAULT_AI_MODEL.into());
-    let target_process_url = target_process::get_base_url();
+    let target_process_url = ctx.tp.base_url().to_string();
     let raw_region = aws::get_region(ctx.profile.clone()).await?;
     let region = raw_region.trim().to_string();
 
     let branch = git::current_branch_v2().await?.to_string();
 
-    let feature_name = branch.split('/').last().unwrap_or(&branch);
+    let feature_name = branch.split('/').next_back().unwrap_or(&branch);
     let tp_link = format!("{target_process_url}/entity/{feature_name}");
 
     let is_valid_tp_branch = utils::get_ticket_id_from_branch(branch.clone()).is_some();
 
-    let mut title = match utils::grab_title(title, branch.clone()).await {
+    let mut title = match utils::grab_title(&ctx.tp, title, branch.clone()).await {
         Ok(v) => v,
         Err(_) => Text::new("Title:")
             .with_placeholder("Your PR Title")
@@ -49,59 +59,35 @@ pub async fn create(
     };
 
     if is_valid_tp_branch && ai_enhance {
+        let (provider, ai_client) = utils::get_ai_client_or_prompt(&mut ctx.config)?;
+        let model = models::select(ai_model, &ctx.config, provider, ai_client.as_ref()).await?;
 
-        let api_key = utils::get_groq_api_key_or_prompt(&mut ctx.config)?;
-        let ai_client = groq::Client::new(&api_key);
-
-        let id = utils::get_ticket_id_from_branch(branch.clone()).ok_or_eyre("Invalid branch name. Cannot extract id")?;
-        let assignable = target_process::get_assignable(id).await?;
-
-        let messages = vec![models::Message::system(
-            r#"
-You're an AI created to help us generate a PR description given a UserStory or Bug. 
-You will be provided with Title, Description, ID, URL and other details about the UserStory or Bug.
+        let id = utils::get_ticket_id_from_branch(branch.clone())
+            .ok_or_eyre("Invalid branch name. Cannot extract id")?;
+        let assignable = target_process::get_assignable(&ctx.tp, id).await?;
 
+        let variables = utils::prompt_variables(&assignable, None);
+        let prompt = utils::render_prompt(PromptKind::PullRequest, &variables).await;
+        let messages = vec![Message::user(prompt)];
 
-The title must be written in present tense and the description should be a brief summary of the changes.
-Use UserStory/Bug info to improve title and description.o
-Don't just copy the title/description, but provide a meaningful content.
+        let payload = ChatPayload::new(&model, messages);
+        let Some(payload) =
+            utils::chat_json_live::<PullRequestContent>(ai_client.as_ref(), payload).await?
+        else {
+            return Ok(());
+        };
 
+        title = payload.title;
 
-Return a json object with the following fields:
-{
-    "title": "PR title",
-    "description": "Pr description"
-}
-
-Return the json data as response. Just that, no other information is needed.
-                "#
-                .to_string(),
-        ), 
-            models::Message::user(serde_json::to_string_pretty(&assignable)?)];
-
-        let response = ai_client.chat(groq::ChatPayload::new(&model, messages)).await?;
-        let content = &response.choices.first().ok_or_eyre("Invalid AI response. No choices returned.")?.message.content;
-        let payload = serde_json::from_str::<HashMap<String, String>>(content)?;
-
-        title = payload.get("title").unwrap_or(&title).to_string();
-
-        if let Some(d) = payload.get("description") {
-            description = d.to_string();
+        if let Some(d) = payload.description {
+            description = d;
             description += &format!("\nSee: {tp_link}")
         }
     }
 
     let base_branch = base;
 
-    let repository = {
-        let raw_url = git::get_remote_url("origin").await?;
-        let url = raw_url.trim();
-
-        match url.split('/').last() {
-            Some(url) => url.trim().replace(".git", ""),
-            None => "".into(),
-        }
-    };
+    let repository = ctx.repository.clone();
 
     println!();
     println!("Check if the details below before proceding:");
@@ -133,8 +119,8 @@ Return the json data as response. Just that, no other information is needed.
         repository.clone(),
         title.clone(),
         description,
-        branch,
-        base_branch,
+        branch.clone(),
+        base_branch.clone(),
         ctx.profile.clone(),
     )
     .await?;
@@ -143,24 +129,65 @@ Return the json data as response. Just that, no other information is needed.
 
     pr_spinner.stop_and_persist("🔗", format!("PR Available at: {pr_link}"));
 
+    events::emit(
+        &ctx.config,
+        Event::new(
+            EventKind::PrCreated,
+            format!(
+                "{} opened a PR to `{repository}`: {}: {title}",
+                ctx.config.pr_name, pr.pull_request.id
+            ),
+        )
+        .link(&pr_link)
+        .with("id", &pr.pull_request.id)
+        .with("title", &title)
+        .with("repository", &repository)
+        .with("source_branch", branch.trim())
+        .with("target_branch", base_branch.trim())
+        .with("author", &ctx.config.pr_name),
+    )
+    .await;
+
     if slack {
-        if let Ok(slack_user_id) = std::env::var("SLACK_USER_ID") {
+        let slack_config = subcommands::slack::current(&ctx.config).await;
+
+        if !slack_config.is_configured() {
+            println!(
+                "{}",
+                "Slack is not configured, run `tpaws slack setup`".yellow()
+            );
+        } else {
             println!();
 
-            let user = select_user()?;
-            println!("Reviewer: {}", user.name.yellow());
+            let author = slack_config
+                .user_id
+                .clone()
+                .or_else(|| std::env::var("SLACK_USER_ID").ok())
+                .map(|id| format!("<@{id}>"))
+                .unwrap_or(ctx.config.pr_name.clone());
+
+            let reviewer = match select_reviewer(&slack_config.reviewers)? {
+                Some(reviewer) => {
+                    println!("Reviewer: {}", reviewer.name.yellow());
+                    format!(" <@{}>", reviewer.id)
+                }
+                None => String::new(),
+            };
 
             let mut slack_spinner = Spinner::new(Spinners::Dots, "Sending slack message".into());
 
-            slack::send_message(
-            format!(
-                "<@{slack_user_id}> opened a PR to: <@{reviewer}> - `{repository}` <{pr_link}|{pr_id}: {title}>",
-                reviewer = user.id,
-                pr_id = pr.pull_request.id,
-            ),
-            pr_link.clone(),
-            tp_link
-        ).await?;
+            subcommands::slack::notify_pr_created(
+                &slack_config,
+                &repository,
+                &pr.pull_request.id,
+                format!(
+                    "{author} opened a PR to:{reviewer} - `{repository}` <{pr_link}|{pr_id}: {title}>",
+                    pr_id = pr.pull_request.id,
+                ),
+                pr_link.clone(),
+                tp_link,
+            )
+            .await?;
 
             slack_spinner.stop_with_symbol("✅");
         }
@@ -176,17 +203,17 @@ Return the json data as response. Just that, no other information is needed.
     Ok(())
 }
 
-fn select_user() -> Result<costants::User> {
+/// Pick a reviewer from the configured directory, `None` when there are none
+fn select_reviewer(reviewers: &[Reviewer]) -> Result<Option<Reviewer>> {
+    if reviewers.is_empty() {
+        return Ok(None);
+    }
+
     let name = Select::new(
         "Who is your reviewer?",
-        costants::USERS.iter().map(|u| u.name).collect(),
+        reviewers.iter().map(|r| r.name.as_str()).collect(),
     )
     .prompt()?;
 
-    let user = costants::USERS.iter().find(|user| user.name == name);
-
-    match user {
-        Some(user) => Ok(user.clone()),
-        None => panic!("invalid value provided: {}", name),
-    }
+    Ok(reviewers.iter().find(|r| r.name == name).cloned())
 }
@@ -0,0 +1,83 @@
+use color_eyre::Result;
+use colored::*;
+use commands::aws;
+use inquire::Confirm;
+
+use crate::{
+    context::GlobalContext,
+    events::{self, Event, EventKind},
+    subcommands::slack::{self, PrEvent},
+};
+
+use super::resolve_pr;
+
+/// Squash merge using the PR title as commit message
+pub async fn merge(ctx: GlobalContext, id: Option<String>, dry_run: bool) -> Result<()> {
+    let pull_request = resolve_pr(&ctx, id).await?;
+
+    println!(
+        "Squash merging {}: {}",
+        pull_request.id,
+        pull_request.title.yellow()
+    );
+
+    if !Confirm::new("Do you confirm?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("Operation aborted.");
+        return Ok(());
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    aws::merge_pr_by_squash(
+        pull_request.id.clone(),
+        ctx.repository.clone(),
+        pull_request.title.clone(),
+        ctx.config.pr_name.clone(),
+        ctx.config.pr_email.clone(),
+        ctx.profile.clone(),
+    )
+    .await?;
+
+    // The PR is already merged, a Slack failure must not hide it
+    if let Err(e) = slack::notify_pr_event(
+        &ctx.config,
+        &ctx.repository,
+        &pull_request.id,
+        PrEvent::Merged,
+    )
+    .await
+    {
+        eprintln!("{}", format!("⚠ Slack thread not updated: {e}").yellow());
+    }
+
+    events::emit(
+        &ctx.config,
+        Event::new(
+            EventKind::PrMerged,
+            format!(
+                "{} merged `{}`: {}: {}",
+                ctx.config.pr_name, ctx.repository, pull_request.id, pull_request.title
+            ),
+        )
+        .link(format!(
+            "https://{region}.console.aws.amazon.com/codesuite/codecommit/repositories/{repository}/pull-requests/{pr_id}/details",
+            region = ctx.region.trim(),
+            repository = ctx.repository,
+            pr_id = pull_request.id
+        ))
+        .with("id", &pull_request.id)
+        .with("title", &pull_request.title)
+        .with("repository", &ctx.repository)
+        .with("author", &ctx.config.pr_name),
+    )
+    .await;
+
+    println!("{}", "Merged".green());
+
+    Ok(())
+}
@@ -1,12 +1,20 @@
+mod approve;
+mod close;
 mod create;
 mod list;
+mod merge;
 mod view;
 
-use color_eyre::Result;
+pub use approve::approve;
+pub use close::close;
+use color_eyre::{eyre::OptionExt, Result};
 pub use create::create;
 pub use list::list;
+pub use merge::merge;
 pub use view::view;
 
+use crate::context::GlobalContext;
+
 use commands::aws::{self, PullRequest, PullRequestStatus};
 
 pub async fn get_current_pr(
@@ -36,3 +44,19 @@ pub async fn get_current_pr(
 
     Ok(pr)
 }
+
+/// PR by id, or the open PR of the current branch
+pub(crate) async fn resolve_pr(ctx: &GlobalContext, id: Option<String>) -> Result<PullRequest> {
+    match id {
+        Some(id) => Ok(aws::get_pull_request(id, ctx.profile.clone())
+            .await?
+            .pull_request),
+        None => get_current_pr(
+            ctx.branch.clone(),
+            ctx.repository.clone(),
+            ctx.profile.clone(),
+        )
+        .await?
+        .ok_or_eyre("No open PR for the current branch"),
+    }
+}
@@ -0,0 +1,151 @@
+use ::slack::models::{
+    escape, Block, BlockType, Button, ButtonStyle, Element, Message, TextBlock, MAX_TEXT_LENGTH,
+};
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use commands::aws;
+use config::Config;
+use semver::Version;
+use spinners::{Spinner, Spinners};
+use target_process::{
+    changelog::{Entry, ReleaseLog},
+    TargetProcessClient,
+};
+
+use crate::subcommands::slack;
+
+/// CodePipeline console link, to the execution when known
+pub(crate) fn pipeline_link(region: &str, name: &str, execution_id: Option<&str>) -> String {
+    let base =
+        format!("https://{region}.console.aws.amazon.com/codesuite/codepipeline/pipelines/{name}");
+
+    match execution_id {
+        Some(id) => format!("{base}/executions/{id}/timeline"),
+        None => format!("{base}/view"),
+    }
+}
+
+/// Split the lines of a group into sections within the Slack text limit
+fn group_sections(title: &str, entries: &[&Entry], base_url: &str) -> Vec<Block> {
+    let mut sections = vec![];
+    let mut text = format!("*{title}*");
+
+    for Entry { id, name, .. } in entries {
+        let line = format!("\n• <{base_url}/entity/{id}|#{id}> {}", escape(name));
+
+        if text.len() + line.len() > MAX_TEXT_LENGTH {
+            sections.push(Block::section(BlockType::Mrkdwn, &text));
+            text = String::new();
+        }
+
+        text += &line;
+    }
+
+    sections.push(Block::section(BlockType::Mrkdwn, text.trim_start()));
+    sections
+}
+
+pub(crate) fn release_message(
+    project: &str,
+    release: &ReleaseLog,
+    base_url: &str,
+    pipeline_link: Option<&str>,
+) -> Message {
+    let features = release.features().collect::<Vec<_>>();
+    let bug_fixes = release.bug_fixes().collect::<Vec<_>>();
+
+    let title = format!("🚀 {project} {} released", release.version);
+    let mut blocks = vec![
+        Block::header(&title),
+        Block::context(vec![Element::Text(TextBlock::mrkdwn(&format!(
+            "{} features · {} bug fixes",
+            features.len(),
+            bug_fixes.len()
+        )))]),
+    ];
+
+    if release.entries.is_empty() {
+        blocks.push(Block::section(
+            BlockType::Mrkdwn,
+            "_No tickets in this release_",
+        ));
+    }
+
+    for (group, entries) in [("Features", features), ("Bug fixes", bug_fixes)] {
+        if !entries.is_empty() {
+            blocks.extend(group_sections(group, &entries, base_url));
+        }
+    }
+
+    if let Some(link) = pipeline_link {
+        blocks.push(Block::divider());
+        blocks.push(Block::actions(vec![
+            Button::link(link, "CodePipeline").style(ButtonStyle::Primary)
+        ]));
+    }
+
+    Message::default().text(Some(title)).blocks(blocks)
+}
+
+/// Post the changelog of `version` to the release channel
+#[allow(clippy::too_many_arguments)]
+pub async fn announce(
+    tp: &TargetProcessClient,
+    version: String,
+    project: String,
+    prefix: String,
+    pipeline_name: Option<String>,
+    profile: String,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let slack_config = slack::current(config).await;
+
+    if !slack_config.is_configured() {
+        return Err(eyre!("Slack is not configured, run `tpaws slack setup`"));
+    }
+
+    let parsed = Version::parse(version.trim_start_matches('v'))
+        .map_err(|e| eyre!("Invalid version `{version}`: {e}"))?;
+
+    let mut spinner = Spinner::new(Spinners::Dots, format!("Announcing {version} ..."));
+
+    let changelog =
+        target_process::generate_changelog(tp, project.clone(), prefix, vec![parsed]).await?;
+
+    if let Some(failure) = changelog.failures.first() {
+        spinner.stop_with_symbol("❌");
+        return Err(eyre!(
+            "Unable to fetch release {}: {}",
+            failure.version,
+            failure.error
+        ));
+    }
+
+    let release = changelog.releases.into_iter().next().unwrap_or(ReleaseLog {
+        version: version.clone(),
+        entries: vec![],
+    });
+
+    let link = match pipeline_name {
+        Some(name) => {
+            let region = aws::get_region(profile).await?;
+            Some(pipeline_link(region.trim(), &name, None))
+        }
+        None => None,
+    };
+
+    let message = release_message(&project, &release, tp.base_url(), link.as_deref());
+
+    if dry_run {
+        spinner.stop_with_newline();
+        println!("{}", serde_json::to_string_pretty(&message)?);
+        return Ok(());
+    }
+
+    slack::post(&slack_config, slack_config.release_channel.clone(), message).await?;
+
+    spinner.stop_and_persist("📣", format!("Release {} announced", version.green()));
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+mod announce;
+
+pub use announce::announce;
+
+use color_eyre::Result;
+use colored::*;
+use commands::git;
+use semver::Version;
+use target_process::{changelog::parse_release_name, TargetProcessClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Bump {
+    pub fn from_flags(major: bool, minor: bool, patch: bool) -> Self {
+        match (major, minor, patch) {
+            (true, _, _) => Self::Major,
+            (_, _, true) => Self::Patch,
+            _ => Self::Minor,
+        }
+    }
+}
+
+pub fn latest_version(tags: &[String], prefix: &str) -> Option<Version> {
+    tags.iter()
+        .filter_map(|t| parse_release_name(t, prefix))
+        .max()
+}
+
+pub fn bump(version: &Version, bump: Bump) -> Version {
+    match bump {
+        Bump::Major => Version::new(version.major + 1, 0, 0),
+        Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+        Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+pub async fn start(
+    tp: &TargetProcessClient,
+    bump_kind: Bump,
+    project: Option<String>,
+    prefix: String,
+    no_target_process: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let tags = git::tags().await?;
+    let current = latest_version(&tags, &prefix).unwrap_or(Version::new(0, 0, 0));
+    let next = bump(&current, bump_kind);
+    let version = next.to_string();
+
+    println!(
+        "Starting release {} (from {})",
+        version.green(),
+        current.to_string().yellow()
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    git::flow::release::start(&version).await?;
+
+    if no_target_process || !tp.has_token() {
+        return Ok(());
+    }
+
+    let Some(project) = project else {
+        println!("No project configured, skipping TargetProcess release");
+        return Ok(());
+    };
+
+    crate::subcommands::tp_release::create(
+        tp,
+        project,
+        target_process::release_name(&prefix, &version),
+        None,
+        None,
+        false,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+    use target_process::changelog::{Entry, ReleaseLog};
+
+    use super::*;
+
+    #[test]
+    fn bump_version() {
+        let tags = vec!["1.9.0".to_string(), "1.10.2".into(), "nightly".into()];
+        let latest = latest_version(&tags, "").unwrap();
+
+        assert_eq!(latest, Version::new(1, 10, 2));
+        assert_eq!(bump(&latest, Bump::Major), Version::new(2, 0, 0));
+        assert_eq!(bump(&latest, Bump::Minor), Version::new(1, 11, 0));
+        assert_eq!(bump(&latest, Bump::Patch), Version::new(1, 10, 3));
+    }
+
+    #[test]
+    fn release_message_splits_long_groups() {
+        let release = ReleaseLog {
+            version: "1.2.0".into(),
+            entries: (0..200)
+                .map(|id| Entry {
+                    id,
+                    name: format!("A rather long user story title number {id}"),
+                    entity_type: "UserStory".into(),
+                })
+                .collect(),
+        };
+
+        let message = announce::release_message(
+            "payments",
+            &release,
+            "https://tp.example.com",
+            Some(&announce::pipeline_link("eu-west-1", "app", Some("42"))),
+        );
+        let value = serde_json::to_value(message).unwrap();
+        let blocks = value["blocks"].as_array().unwrap();
+
+        assert_eq!(blocks[0]["text"]["text"], "🚀 payments 1.2.0 released");
+
+        let sections = blocks
+            .iter()
+            .filter(|b| b["type"] == "section")
+            .collect::<Vec<_>>();
+        assert!(sections.len() > 1);
+        assert!(sections
+            .iter()
+            .all(|s| s["text"]["text"].as_str().unwrap().len() <= slack::models::MAX_TEXT_LENGTH));
+
+        let button = &blocks.last().unwrap()["elements"][0];
+        assert_eq!(
+            button["url"],
+            "https://eu-west-1.console.aws.amazon.com/codesuite/codepipeline/pipelines/app/executions/42/timeline"
+        );
+    }
+}
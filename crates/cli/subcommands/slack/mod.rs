@@ -0,0 +1,280 @@
+pub mod reviewers;
+mod threads;
+
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
+use colored::*;
+use config::{Config, ProjectConfig, Setting, SlackConfig};
+use inquire::Text;
+
+use threads::SlackThread;
+
+/// Slack settings stored in the global config or in the project `tpaws.json`
+pub(crate) async fn read(project: bool, config: &Config) -> Result<SlackConfig> {
+    if project {
+        let project = ProjectConfig::read()
+            .await
+            .ok_or_eyre("No tpaws.json found, run `tpaws init` first")?;
+
+        return Ok(project.slack.unwrap_or_default());
+    }
+
+    Ok(config.slack.clone().unwrap_or_default())
+}
+
+pub(crate) async fn write(
+    slack: SlackConfig,
+    project: bool,
+    config: &mut Config,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        println!("{}", serde_json::to_string_pretty(&slack)?);
+        return Ok(());
+    }
+
+    if project {
+        let mut project = ProjectConfig::read()
+            .await
+            .ok_or_eyre("No tpaws.json found, run `tpaws init` first")?;
+
+        let mut slack = slack;
+        slack.remove_secrets();
+
+        project.slack = Some(slack);
+        return project.write();
+    }
+
+    config.slack = Some(slack);
+    config.write()
+}
+
+/// Slack settings in use: project over global, the channel can be set through the env
+pub(crate) async fn current(config: &Config) -> SlackConfig {
+    let project = ProjectConfig::read().await;
+    let mut slack = SlackConfig::merge(
+        config.slack.as_ref(),
+        project.as_ref().and_then(|p| p.slack.as_ref()),
+    );
+
+    slack.channel = Setting::SlackChannel.value(None, project.as_ref(), config);
+    slack
+}
+
+pub async fn setup(project: bool, config: &mut Config, dry_run: bool) -> Result<()> {
+    let mut slack = read(project, config).await?;
+
+    // tpaws.json is committed, the webhook and the bot token stay in the global config
+    if project {
+        println!(
+            "{}",
+            "The webhook and the bot token are set with `tpaws slack setup`, without --project"
+                .dimmed()
+        );
+    } else {
+        slack.webhook_url = Text::new("Webhook URL:")
+            .with_help_message("https://api.slack.com/messaging/webhooks")
+            .with_default(&slack.webhook_url.unwrap_or_default())
+            .prompt_skippable()?
+            .filter(|v| !v.is_empty());
+
+        slack.bot_token = Text::new("Bot token:")
+            .with_help_message(
+                "xoxb-..., enables threaded PR updates. Leave empty to use the webhook",
+            )
+            .with_default(&slack.bot_token.unwrap_or_default())
+            .prompt_skippable()?
+            .filter(|v| !v.is_empty());
+    }
+
+    slack.channel = Text::new("Channel:")
+        .with_help_message("required with a bot token, otherwise the webhook default is used")
+        .with_default(&slack.channel.unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|v| !v.is_empty());
+
+    slack.release_channel = Text::new("Release channel:")
+        .with_help_message(
+            "where `tpaws release announce` posts the changelog, defaults to the channel",
+        )
+        .with_default(&slack.release_channel.unwrap_or_default())
+        .prompt_skippable()?
+        .filter(|v| !v.is_empty());
+
+    slack.user_id = Text::new("Your Slack member ID:")
+        .with_help_message("Profile > ⋮ > Copy member ID")
+        .with_default(
+            &slack
+                .user_id
+                .or_else(|| std::env::var("SLACK_USER_ID").ok())
+                .unwrap_or_default(),
+        )
+        .prompt_skippable()?
+        .filter(|v| !v.is_empty());
+
+    write(slack, project, config, dry_run).await?;
+
+    println!("{}", "Slack configured".green());
+
+    Ok(())
+}
+
+/// Post a message to `channel` with the bot token, or to the webhook
+pub(crate) async fn post(
+    slack: &SlackConfig,
+    channel: Option<String>,
+    message: slack::models::Message,
+) -> Result<()> {
+    let message = message.channel(channel.or(slack.channel.clone()));
+
+    if slack.is_bot() {
+        let client = slack::web::Client::new(slack.bot_token.as_deref().unwrap_or_default())?;
+        client.post_message(&message).await?;
+
+        return Ok(());
+    }
+
+    let webhook_url = slack
+        .webhook_url
+        .as_deref()
+        .ok_or_eyre("Slack is not configured, run `tpaws slack setup`")?;
+
+    slack::send(webhook_url, &message).await?;
+
+    Ok(())
+}
+
+/// Lifecycle events posted in the thread of the PR notification
+#[derive(Debug, Clone, Copy, strum::Display)]
+pub(crate) enum PrEvent {
+    #[strum(serialize = "Approved")]
+    Approved,
+
+    #[strum(serialize = "Merged")]
+    Merged,
+
+    #[strum(serialize = "Closed")]
+    Closed,
+}
+
+impl PrEvent {
+    fn emoji(&self) -> &'static str {
+        match self {
+            PrEvent::Approved => ":white_check_mark:",
+            PrEvent::Merged => ":tada:",
+            PrEvent::Closed => ":no_entry_sign:",
+        }
+    }
+
+    /// Merged and closed PRs won't receive other updates
+    fn is_final(&self) -> bool {
+        !matches!(self, PrEvent::Approved)
+    }
+}
+
+/// Notify a new PR, through the Web API when a bot token is configured
+pub(crate) async fn notify_pr_created(
+    slack: &SlackConfig,
+    repository: &str,
+    pr_id: &str,
+    content: String,
+    pr_link: String,
+    tp_link: String,
+) -> Result<()> {
+    if slack.is_bot() {
+        let client = slack::web::Client::new(slack.bot_token.as_deref().unwrap_or_default())?;
+        let message =
+            slack::pr_message(&content, &pr_link, &tp_link).channel(slack.channel.clone());
+        let posted = client.post_message(&message).await?;
+
+        return threads::save(
+            repository,
+            pr_id,
+            SlackThread {
+                channel: posted.channel,
+                ts: posted.ts,
+                content,
+                pr_link,
+                tp_link,
+            },
+        );
+    }
+
+    let webhook_url = slack
+        .webhook_url
+        .as_deref()
+        .ok_or_eyre("Slack is not configured, run `tpaws slack setup`")?;
+
+    slack::send_message(
+        webhook_url,
+        slack.channel.clone(),
+        content,
+        pr_link,
+        tp_link,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Reply in the PR thread and, for final events, edit the original message
+///
+/// Does nothing without a bot token or when the PR was not announced by tpaws
+pub(crate) async fn notify_pr_event(
+    config: &Config,
+    repository: &str,
+    pr_id: &str,
+    event: PrEvent,
+) -> Result<()> {
+    let slack = current(config).await;
+
+    if !slack.is_bot() {
+        return Ok(());
+    }
+
+    let Some(thread) = threads::get(repository, pr_id) else {
+        return Ok(());
+    };
+
+    let client = slack::web::Client::new(slack.bot_token.as_deref().unwrap_or_default())?;
+    let author = slack
+        .user_id
+        .map(|id| format!("<@{id}>"))
+        .unwrap_or(config.pr_name.clone());
+
+    let reply = format!("{} {event} by {author}", event.emoji());
+    client
+        .reply(
+            &thread.message(),
+            slack::models::Message::default()
+                .text(Some(reply.clone()))
+                .blocks(vec![slack::models::Block::section(
+                    slack::models::BlockType::Mrkdwn,
+                    &reply,
+                )]),
+        )
+        .await
+        .map_err(|e| eyre!("Unable to reply in the PR thread: {e}"))?;
+
+    if event.is_final() {
+        let content = format!(
+            "{} *{}* ~{}~",
+            event.emoji(),
+            event.to_string().to_uppercase(),
+            thread.content
+        );
+
+        client
+            .update_message(
+                &thread.message(),
+                slack::pr_message(&content, &thread.pr_link, &thread.tp_link),
+            )
+            .await?;
+
+        threads::remove(repository, pr_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use config::{Config, ProjectConfig, Reviewer};
+
+pub async fn add(
+    name: String,
+    id: String,
+    project: bool,
+    config: &mut Config,
+    dry_run: bool,
+) -> Result<()> {
+    let mut slack = super::read(project, config).await?;
+    slack.add_reviewer(Reviewer {
+        name: name.clone(),
+        id,
+    });
+
+    super::write(slack, project, config, dry_run).await?;
+
+    println!("Added {}", name.green());
+
+    Ok(())
+}
+
+pub async fn remove(
+    name_or_id: String,
+    project: bool,
+    config: &mut Config,
+    dry_run: bool,
+) -> Result<()> {
+    let mut slack = super::read(project, config).await?;
+
+    if !slack.remove_reviewer(&name_or_id) {
+        return Err(eyre!("No reviewer matching `{name_or_id}`"));
+    }
+
+    super::write(slack, project, config, dry_run).await?;
+
+    println!("Removed {}", name_or_id.green());
+
+    Ok(())
+}
+
+pub async fn list(config: &Config) -> Result<()> {
+    let project = ProjectConfig::read()
+        .await
+        .and_then(|p| p.slack)
+        .unwrap_or_default();
+    let slack = super::current(config).await;
+
+    if slack.reviewers.is_empty() {
+        println!("No reviewers, add one with `tpaws slack reviewers add <name> <id>`");
+        return Ok(());
+    }
+
+    for reviewer in slack.reviewers {
+        let origin = match project.reviewers.iter().any(|r| r.id == reviewer.id) {
+            true => "project",
+            false => "global",
+        };
+
+        println!(
+            "{:<24} {:<14} {}",
+            reviewer.name.bold(),
+            reviewer.id,
+            origin.dimmed()
+        );
+    }
+
+    Ok(())
+}
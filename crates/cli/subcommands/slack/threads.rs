@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use slack::web::PostedMessage;
+
+/// PR notification posted in bot mode, kept to thread later events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SlackThread {
+    pub channel: String,
+    pub ts: String,
+
+    /// Original content, re-rendered with the final status
+    pub content: String,
+    pub pr_link: String,
+    pub tp_link: String,
+}
+
+impl SlackThread {
+    pub fn message(&self) -> PostedMessage {
+        PostedMessage {
+            channel: self.channel.clone(),
+            ts: self.ts.clone(),
+        }
+    }
+}
+
+fn key(repository: &str, pr_id: &str) -> String {
+    format!("{repository}/{pr_id}")
+}
+
+fn read_all() -> HashMap<String, SlackThread> {
+    config::data_dir()
+        .and_then(|dir| Ok(std::fs::read_to_string(dir.join("slack_threads.json"))?))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_all(threads: &HashMap<String, SlackThread>) -> Result<()> {
+    let dir = config::data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("slack_threads.json"),
+        serde_json::to_string_pretty(threads)?,
+    )?;
+
+    Ok(())
+}
+
+pub(crate) fn get(repository: &str, pr_id: &str) -> Option<SlackThread> {
+    read_all().remove(&key(repository, pr_id))
+}
+
+pub(crate) fn save(repository: &str, pr_id: &str, thread: SlackThread) -> Result<()> {
+    let mut threads = read_all();
+    threads.insert(key(repository, pr_id), thread);
+
+    write_all(&threads)
+}
+
+pub(crate) fn remove(repository: &str, pr_id: &str) -> Result<()> {
+    let mut threads = read_all();
+
+    if threads.remove(&key(repository, pr_id)).is_some() {
+        write_all(&threads)?;
+    }
+
+    Ok(())
+}
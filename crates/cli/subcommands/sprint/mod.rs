@@ -0,0 +1,136 @@
+use chrono::Local;
+use color_eyre::Result;
+use colored::*;
+use target_process::{models::v2::iteration::Iteration, TargetProcessClient};
+
+pub async fn overview(tp: &TargetProcessClient, project: String, json: bool) -> Result<()> {
+    let iterations = target_process::get_current_iterations(tp, &project).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&iterations)?);
+        return Ok(());
+    }
+
+    if iterations.is_empty() {
+        println!("No current iteration found for `{project}`");
+        return Ok(());
+    }
+
+    for (i, iteration) in iterations.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        print_iteration(iteration);
+    }
+
+    Ok(())
+}
+
+/// Every team of the project has its own current iteration
+fn print_iteration(iteration: &Iteration) {
+    print_header(iteration);
+
+    for (state, assignables) in iteration.group_by_state() {
+        println!();
+        println!("{} ({})", state.bold(), assignables.len());
+
+        for assignable in assignables {
+            let effort = assignable
+                .effort
+                .map(|e| format!("{e}pt"))
+                .unwrap_or("-".into());
+
+            let assignees = match assignable.assignees() {
+                a if a.is_empty() => "unassigned".dimmed().to_string(),
+                a => a.blue().to_string(),
+            };
+
+            println!(
+                "  [{id}] {name} - {assignees} ({effort})",
+                id = assignable.id.to_string().green(),
+                name = assignable.name,
+                effort = effort.yellow()
+            );
+        }
+    }
+
+    println!();
+    print_burndown(iteration);
+}
+
+fn print_header(iteration: &Iteration) {
+    let today = Local::now().date_naive();
+    let team_iteration = &iteration.team_iteration;
+
+    println!("{}", team_iteration.name.bold());
+    println!("===================");
+
+    if let (Some(start), Some(end)) = (team_iteration.start_date, team_iteration.end_date) {
+        println!("{} → {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+    }
+
+    if let Some(days_left) = iteration.days_left(today) {
+        let label = match days_left {
+            d if d < 0 => "iteration is over".red().to_string(),
+            0 => "last day".yellow().to_string(),
+            d => format!("{d} days left").yellow().to_string(),
+        };
+
+        println!("{label}");
+    }
+}
+
+/// Share of the effort done, the logged time can exceed the estimate
+fn progress(completed: f64, total: f64) -> f64 {
+    match total > 0.0 {
+        true => (completed / total).clamp(0.0, 1.0),
+        false => 0.0,
+    }
+}
+
+fn print_burndown(iteration: &Iteration) {
+    let today = Local::now().date_naive();
+
+    let total = iteration.total_effort();
+    let completed = iteration.completed_effort();
+    let remaining = iteration.remaining_effort();
+
+    let progress = progress(completed, total);
+    let width = 30;
+    let filled = (progress * width as f64).round() as usize;
+
+    println!(
+        "[{}{}] {:.0}%",
+        "#".repeat(filled).green(),
+        "-".repeat(width - filled),
+        progress * 100.0
+    );
+
+    println!("Effort: {completed}/{total}pt done, {remaining}pt remaining");
+
+    if let Some(ideal) = iteration.ideal_remaining_effort(today) {
+        let delta = remaining - ideal;
+
+        let status = match delta {
+            d if d > 0.0 => format!("behind by {d:.1}pt").red(),
+            d if d < 0.0 => format!("ahead by {:.1}pt", -d).green(),
+            _ => "on track".green(),
+        };
+
+        println!("Ideal remaining: {ideal:.1}pt ({status})");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn progress_is_clamped() {
+        assert_eq!(progress(5.0, 10.0), 0.5);
+        assert_eq!(progress(12.0, 10.0), 1.0);
+        assert_eq!(progress(-1.0, 10.0), 0.0);
+        assert_eq!(progress(3.0, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{Days, Local, NaiveDate};
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use commands::git;
+use spinners::{Spinner, Spinners};
+use target_process::TargetProcessClient;
+
+use crate::utils;
+
+/// Default length of a TargetProcess release when no end date is given
+const DEFAULT_RELEASE_DAYS: u64 = 14;
+
+pub async fn create(
+    tp: &TargetProcessClient,
+    project: String,
+    name: String,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    dry_run: bool,
+) -> Result<()> {
+    if let Some(release) = target_process::get_release(tp, &project, &name).await? {
+        println!(
+            "Release {} already exists (#{})",
+            release.name.yellow(),
+            release.id
+        );
+        return Ok(());
+    }
+
+    let start = start.unwrap_or(Local::now().date_naive());
+    let end = end.unwrap_or(start + Days::new(DEFAULT_RELEASE_DAYS));
+
+    if dry_run {
+        println!("Would create release {} ({start} → {end})", name.yellow());
+        return Ok(());
+    }
+
+    let mut spinner = Spinner::new(Spinners::Dots, format!("Creating release {name} ..."));
+    let release = target_process::create_release(tp, &project, &name, start, end).await?;
+
+    spinner.stop_and_persist(
+        "✅",
+        format!("Release {} created (#{})", release.name, release.id),
+    );
+
+    Ok(())
+}
+
+/// Release name for the current `release/x.y.z` branch
+async fn release_from_branch(prefix: &str) -> Result<String> {
+    let branch = git::current_branch_v2().await?;
+
+    if !branch.is_release() {
+        return Err(eyre!(
+            "Unable to infer the release from `{branch}`, please provide `--release`"
+        ));
+    }
+
+    let version = branch.0.trim_start_matches("release/");
+
+    Ok(target_process::release_name(prefix, version))
+}
+
+pub async fn add(
+    tp: &TargetProcessClient,
+    project: String,
+    release: Option<String>,
+    prefix: String,
+    ids: Vec<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let name = match release {
+        Some(name) => name,
+        None => release_from_branch(&prefix).await?,
+    };
+
+    let release = target_process::get_release(tp, &project, &name)
+        .await?
+        .ok_or(eyre!("Release `{name}` not found in `{project}`"))?;
+
+    for id_or_url in ids {
+        let id = utils::extract_id_from_url(id_or_url.clone()).unwrap_or(id_or_url);
+        let assignable_id: usize = id.parse().map_err(|_| eyre!("Invalid ticket id: {id}"))?;
+
+        if dry_run {
+            println!("Would add #{assignable_id} to {}", release.name.yellow());
+            continue;
+        }
+
+        let assignable = target_process::assign_release(tp, assignable_id, release.id).await?;
+
+        println!(
+            "Added [{}] {} to {}",
+            assignable.id.to_string().green(),
+            assignable.name,
+            release.name.yellow()
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn show(
+    tp: &TargetProcessClient,
+    project: String,
+    name: String,
+    json: bool,
+) -> Result<()> {
+    let release = target_process::get_release(tp, &project, &name)
+        .await?
+        .ok_or(eyre!("Release `{name}` not found in `{project}`"))?;
+
+    let assignables = target_process::get_release_assignables(tp, &project, &name).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&assignables)?);
+        return Ok(());
+    }
+
+    println!("{}", release.name.bold());
+    println!("===================");
+
+    if let (Some(start), Some(end)) = (release.start_date, release.end_date) {
+        println!("{} → {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+    }
+
+    if assignables.is_empty() {
+        println!();
+        println!("No tickets in this release");
+        return Ok(());
+    }
+
+    println!();
+
+    for assignable in assignables {
+        println!(
+            "[{id}] {name} - {kind} ({state})",
+            id = assignable.id.to_string().green(),
+            name = assignable.name,
+            kind = assignable.entity_type.name.blue(),
+            state = assignable.entity_state.name.yellow()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
+use colored::*;
+use spinners::{Spinner, Spinners};
+use target_process::TargetProcessClient;
+use tokio::io::AsyncWriteExt;
+
+use crate::utils;
+
+pub async fn attachments(
+    tp: &TargetProcessClient,
+    id_or_url: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let id = utils::extract_id(id_or_url).await?;
+    let attachments = target_process::get_attachments(tp, id.clone()).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&attachments)?);
+        return Ok(());
+    }
+
+    if attachments.is_empty() {
+        println!("No attachments found for #{id}");
+        return Ok(());
+    }
+
+    for attachment in attachments {
+        println!(
+            "[{id}] {name} ({size})",
+            id = attachment.id.to_string().green(),
+            name = attachment.name,
+            size = attachment.human_size().yellow()
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn download(
+    tp: &TargetProcessClient,
+    id_or_url: Option<String>,
+    output: Option<PathBuf>,
+    name: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let id = utils::extract_id(id_or_url).await?;
+    let output = output.unwrap_or(PathBuf::from("."));
+
+    let attachments: Vec<_> = target_process::get_attachments(tp, id.clone())
+        .await?
+        .into_iter()
+        .filter(|a| name.as_ref().is_none_or(|n| &a.name == n))
+        .collect();
+
+    if attachments.is_empty() {
+        println!("No attachments found for #{id}");
+        return Ok(());
+    }
+
+    tokio::fs::create_dir_all(&output).await?;
+
+    let mut used = HashSet::new();
+
+    for attachment in attachments {
+        let Some(file_name) = file_name(&attachment.name, attachment.id, &mut used) else {
+            println!(
+                "{}",
+                format!("Skipping `{}`: unsafe file name", attachment.name).yellow()
+            );
+            continue;
+        };
+
+        let path = output.join(file_name);
+
+        if !force && path.exists() {
+            println!(
+                "{}",
+                format!(
+                    "Skipping `{}`: {} already exists, use --force to replace it",
+                    attachment.name,
+                    path.display()
+                )
+                .yellow()
+            );
+            continue;
+        }
+
+        let uri = attachment
+            .uri
+            .as_ref()
+            .ok_or_eyre(format!("No download url for `{}`", attachment.name))?;
+
+        let mut spinner = Spinner::new(
+            Spinners::Dots,
+            format!("Downloading {} ...", attachment.name),
+        );
+
+        let content = tp.download(uri).await?;
+        save(&path, &content, force).await?;
+
+        spinner.stop_and_persist("✅", format!("Saved {}", path.display()));
+    }
+
+    Ok(())
+}
+
+/// Local name of an attachment, the names come from the server: only a plain file name
+/// is kept and `-<id>` is appended when it is already `used`
+fn file_name(name: &str, id: usize, used: &mut HashSet<String>) -> Option<String> {
+    let path = Path::new(name);
+
+    if name.contains('\\')
+        || path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+
+    let name = path.file_name()?.to_str()?.to_string();
+
+    if used.insert(name.clone()) {
+        return Some(name);
+    }
+
+    let unique = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}-{id}.{ext}"),
+        _ => format!("{name}-{id}"),
+    };
+    used.insert(unique.clone());
+
+    Some(unique)
+}
+
+/// Write `content` to `path`, an existing file is only replaced with `force`
+async fn save(path: &Path, content: &[u8], force: bool) -> Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true);
+
+    match force {
+        true => options.create(true).truncate(true),
+        false => options.create_new(true),
+    };
+
+    let mut file = options.open(path).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => eyre!("{} already exists", path.display()),
+        _ => eyre!("Unable to write {}: {e}", path.display()),
+    })?;
+
+    file.write_all(content).await?;
+    file.flush().await?;
+
+    Ok(())
+}
+
+pub async fn attach(
+    tp: &TargetProcessClient,
+    file: &Path,
+    id_or_url: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    if !file.is_file() {
+        return Err(eyre!("File not found: {}", file.display()));
+    }
+
+    let id = utils::extract_id(id_or_url).await?;
+    let general_id: usize = id.parse().map_err(|_| eyre!("Invalid ticket id: {id}"))?;
+
+    if dry_run {
+        println!("Would upload {} to #{general_id}", file.display());
+        return Ok(());
+    }
+
+    let mut spinner = Spinner::new(Spinners::Dots, format!("Uploading {} ...", file.display()));
+    let attachment = target_process::upload_attachment(tp, general_id, file).await?;
+
+    spinner.stop_and_persist(
+        "📎",
+        format!("Attached `{}` to #{general_id}", attachment.name),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn safe_file_names() {
+        let mut used = HashSet::new();
+
+        assert_eq!(
+            file_name("screenshot.png", 1, &mut used).as_deref(),
+            Some("screenshot.png")
+        );
+        assert_eq!(
+            file_name("screenshot.png", 2, &mut used).as_deref(),
+            Some("screenshot-2.png")
+        );
+        assert_eq!(
+            file_name("logs/app.log", 3, &mut used).as_deref(),
+            Some("app.log")
+        );
+
+        assert_eq!(file_name("../../.bashrc", 4, &mut used), None);
+        assert_eq!(file_name("/etc/passwd", 5, &mut used), None);
+        assert_eq!(file_name("..\\evil.exe", 6, &mut used), None);
+        assert_eq!(file_name("", 7, &mut used), None);
+    }
+
+    #[tokio::test]
+    async fn existing_files_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        std::fs::write(&path, "mine").unwrap();
+
+        assert!(save(&path, b"attachment", false).await.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "mine");
+
+        save(&path, b"attachment", true).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "attachment");
+
+        let new = dir.path().join("screenshot.png");
+        save(&new, b"png", false).await.unwrap();
+        assert_eq!(std::fs::read(&new).unwrap(), b"png");
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::PathBuf;
+
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
+use colored::*;
+use commands::git;
+use target_process::changelog::{
+    self, existing_versions, parse_release_name, parse_version_bound, render, Changelog,
+    ChangelogFormat, Entry, ReleaseFailure, ReleaseLog,
+};
+use target_process::TargetProcessClient;
+use tokio::task::JoinSet;
+
+use crate::{cli::ChangelogSource, utils};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn changelog(
+    tp: &TargetProcessClient,
+    from: Option<String>,
+    to: Option<String>,
+    from_git: Option<String>,
+    project: Option<String>,
+    prefix: String,
+    source: ChangelogSource,
+    format: ChangelogFormat,
+    no_title: bool,
+    output: Option<PathBuf>,
+    prepend: bool,
+) -> Result<()> {
+    if prepend && !format.supports_prepend(no_title) {
+        return Err(match no_title {
+            true => eyre!("`--prepend` needs the version titles, remove `--no-title`"),
+            false => eyre!("`--prepend` is not supported with `--format {format}`"),
+        });
+    }
+
+    let Changelog {
+        mut releases,
+        failures,
+    } = match from_git {
+        Some(range) => changelog_from_git(tp, &range).await?,
+        None => {
+            let project = project.ok_or_eyre("Unable to extract project")?;
+            changelog_from_releases(tp, from, to, project, prefix, source).await?
+        }
+    };
+
+    for failure in &failures {
+        eprintln!(
+            "{} unable to fetch {}: {}",
+            "warning:".yellow(),
+            failure.version,
+            failure.error
+        );
+    }
+
+    let Some(output) = output else {
+        if releases.is_empty() {
+            println!("Empty changelog :(");
+            return Ok(());
+        }
+
+        println!("{}", render(&releases, format, no_title, tp.base_url()));
+        return Ok(());
+    };
+
+    let existing = match prepend {
+        true => match tokio::fs::read_to_string(&output).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(eyre!("Unable to read {}: {e}", output.display())),
+        },
+        false => String::new(),
+    };
+
+    let present = existing_versions(&existing);
+    releases.retain(|r| !present.contains(&r.version));
+
+    if releases.is_empty() {
+        println!("{} is already up to date", output.display());
+        return Ok(());
+    }
+
+    let section = render(&releases, format, no_title, tp.base_url());
+
+    let content = match prepend {
+        true if existing.trim().is_empty() && format == ChangelogFormat::KeepAChangelog => {
+            changelog::prepend("# Changelog\n\n", &section)
+        }
+        true => changelog::prepend(&existing, &section),
+        false => format!("{section}\n"),
+    };
+
+    tokio::fs::write(&output, content).await?;
+
+    println!(
+        "Added {} version(s) to {}",
+        releases.len().to_string().green(),
+        output.display().to_string().yellow()
+    );
+
+    Ok(())
+}
+
+async fn changelog_from_releases(
+    tp: &TargetProcessClient,
+    from: Option<String>,
+    to: Option<String>,
+    project: String,
+    prefix: String,
+    source: ChangelogSource,
+) -> Result<Changelog> {
+    let from = from
+        .map(|v| parse_version_bound(&v, false).ok_or(eyre!("Invalid version `{v}`")))
+        .transpose()?;
+
+    let to = to
+        .map(|v| parse_version_bound(&v, true).ok_or(eyre!("Invalid version `{v}`")))
+        .transpose()?;
+
+    let versions = match source {
+        ChangelogSource::Git => git::tags()
+            .await?
+            .iter()
+            .filter_map(|tag| parse_release_name(tag, &prefix))
+            .collect(),
+        ChangelogSource::TargetProcess => {
+            changelog::get_release_versions(tp, &project, &prefix).await?
+        }
+    };
+
+    let versions = changelog::filter_versions(versions, from.as_ref(), to.as_ref());
+
+    Ok(target_process::generate_changelog(tp, project, prefix, versions).await?)
+}
+
+/// Single release made of the tickets referenced by the commits of `range`
+async fn changelog_from_git(tp: &TargetProcessClient, range: &str) -> Result<Changelog> {
+    let range = match range.contains("..") {
+        true => range.to_string(),
+        false => format!("{range}..HEAD"),
+    };
+
+    let version = match range.split("..").nth(1) {
+        Some(end) if !end.is_empty() && end != "HEAD" => end.to_string(),
+        _ => "Unreleased".to_string(),
+    };
+
+    let mut ids: Vec<String> = vec![];
+
+    for commit in git::log(&range).await? {
+        for id in utils::get_ticket_ids_from_message(&commit.message) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    let mut handles = JoinSet::new();
+
+    for id in ids.clone() {
+        let tp = tp.clone();
+        handles.spawn(async move { (id.clone(), target_process::get_assignable(&tp, id).await) });
+    }
+
+    let mut changelog = Changelog::default();
+    let mut entries: Vec<Entry> = vec![];
+
+    while let Some(joined) = handles.join_next().await {
+        match joined? {
+            (_, Ok(assignable)) => entries.push(Entry {
+                id: assignable.id,
+                name: assignable.name,
+                entity_type: assignable.entity_type.name,
+            }),
+            (id, Err(error)) => changelog.failures.push(ReleaseFailure {
+                version: format!("#{id}"),
+                error,
+            }),
+        }
+    }
+
+    // keep the commit order, newest first
+    entries.sort_by_key(|e| ids.iter().position(|id| *id == e.id.to_string()));
+
+    if !entries.is_empty() {
+        changelog.releases.push(ReleaseLog { version, entries });
+    }
+
+    Ok(changelog)
+}
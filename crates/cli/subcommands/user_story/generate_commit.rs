@@ -1,65 +1,64 @@
-use crate::utils;
-use ai::groq;
-use color_eyre::{eyre::OptionExt, Result};
-use config::Config;
-use inquire::Text;
-use target_process::models::assignable::Assignable;
-
-#[derive(serde::Deserialize, serde::Serialize)]
+use crate::{subcommands::ai::models, utils};
+use ai::models::{ChatPayload, Message};
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use commands::git;
+use config::{prompts::PromptKind, Config};
+use inquire::Confirm;
+use target_process::{models::assignable::Assignable, TargetProcessClient};
+
+#[derive(serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
 pub struct CommitMessage {
     message: String,
     description: Option<String>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_commit(
+    tp: &TargetProcessClient,
     id_or_url: Option<String>,
     json: bool,
     title_only: bool,
+    diff: bool,
+    commit: bool,
+    model: Option<String>,
+    dry_run: bool,
     config: &mut Config,
 ) -> Result<()> {
-    let groq_api_key = match utils::get_groq_api_key(config) {
-        Ok(k) => k,
-        Err(e) => match Text::new("Enter your Groq API key:")
-            .with_placeholder("gsk_XX")
-            .prompt_skippable()?
-        {
-            None => return Err(e),
-            Some(k) => {
-                config.update_groq_api_key(&k);
-                config.write()?;
-                k
-            }
-        },
-    };
+    let (provider, ai) = utils::get_ai_client_or_prompt(config)?;
+    let model = models::select(model, config, provider, ai.as_ref()).await?;
 
     let id = utils::extract_id(id_or_url).await?;
 
-    let assignable = target_process::get_assignable(id).await?;
-    let messages = get_messages(assignable);
-    let payload = groq::ChatPayload::new("llama3-8b-8192", messages);
+    let diff = match diff {
+        true => Some(get_staged_diff().await?),
+        false => None,
+    };
+
+    let assignable = target_process::get_assignable(tp, id).await?;
+    let messages = get_messages(&assignable, diff).await;
+    let payload = ChatPayload::new(&model, messages);
 
-    let ai = groq::Client::new(&groq_api_key);
-    let response = ai.chat(payload).await?;
-    let content = &response
-        .choices
-        .first()
-        .ok_or_eyre("Invalid AI response. No choices returned.")?
-        .message
-        .content;
+    let Some(commit_message) = utils::chat_json_live::<CommitMessage>(ai.as_ref(), payload).await?
+    else {
+        return Ok(());
+    };
 
-    let commit = serde_json::from_str::<CommitMessage>(content)?;
+    if commit {
+        return commit_changes(commit_message, title_only, dry_run).await;
+    }
 
     if json {
-        let str = serde_json::to_string_pretty(&commit)?;
+        let str = serde_json::to_string_pretty(&commit_message)?;
         println!("{}", str);
 
         return Ok(());
     }
 
-    println!("{}", commit.message);
+    println!("{}", commit_message.message);
 
     if !title_only {
-        if let Some(description) = commit.description {
+        if let Some(description) = commit_message.description {
             println!("{}", description);
         }
     }
@@ -67,52 +66,49 @@ pub async fn generate_commit(
     Ok(())
 }
 
-fn get_messages(assignable: Assignable) -> Vec<groq::models::Message> {
-    let system_prompt = groq::models::Message::system(
-        r#"
-                You're an ai specialist, you should know how to generate a commit message.
-                Given ID, title and content of a User Story generate a commit message for it.
-
-                The format should follow conventional commits.
-                    - "feat(<id>): <message>" -> user stories
-                    - "fix(<id>): <message>" -> bugs
-
-                Return the commit message as response. Just that, no other information is needed.
-                On a new line, add a longer description for the commit message if needed.
-
-                The commit message must be lowercased and in present tense.
-                Don't just copy the title, but provide a meaningful message.
-
-                Return a JSON object with the following structure:
-                {
-                    "message": "feat(123): add new feature",
-                    "description": "This feature will allow users to do X and Y" // optional
-                }
-                "#
-        .to_string(),
-    );
-
-    let Assignable {
-        id,
-        name,
-        description,
-        entity_type,
-        ..
-    } = assignable;
-
-    let message = groq::models::Message::user(format!(
-        r#"
-                    ID: {id}
-                    Title: {name}
-                    Type: {}
-                    Description:
-                    {}
-                    "#,
-        entity_type.name,
-        &description
-            .clone()
-            .get_or_insert("No description provided".to_string())
-    ));
-
-    vec![system_prompt, message]
+async fn get_staged_diff() -> Result<String> {
+    let diff = git::staged_diff().await?;
+
+    if diff.trim().is_empty() {
+        return Err(eyre!(
+            "No staged changes. Use `git add` before generating the commit"
+        ));
+    }
+
+    Ok(format!(
+        "Staged changes:\n{}",
+        utils::summarize_diff(&diff, utils::MAX_DIFF_CHARS)
+    ))
+}
+
+async fn commit_changes(commit: CommitMessage, title_only: bool, dry_run: bool) -> Result<()> {
+    let description = commit.description.filter(|_| !title_only);
+
+    println!("\n{}", commit.message.bold());
+    if let Some(description) = &description {
+        println!("\n{}", description);
+    }
+    println!();
+
+    if dry_run {
+        println!("{}", "Dry run, skipping git commit".yellow());
+        return Ok(());
+    }
+
+    let confirm = Confirm::new("Commit with this message?")
+        .with_default(true)
+        .prompt()?;
+
+    if !confirm {
+        return Ok(());
+    }
+
+    git::commit(&commit.message, description.as_deref()).await
+}
+
+async fn get_messages(assignable: &Assignable, diff: Option<String>) -> Vec<Message> {
+    let variables = utils::prompt_variables(assignable, diff);
+    let prompt = utils::render_prompt(PromptKind::Commit, &variables).await;
+
+    vec![Message::user(prompt)]
 }
@@ -1,9 +1,10 @@
 use crate::utils;
 use color_eyre::Result;
+use target_process::TargetProcessClient;
 
-pub async fn get_branch(id_or_url: String) -> Result<()> {
+pub async fn get_branch(tp: &TargetProcessClient, id_or_url: String) -> Result<()> {
     let id = utils::extract_id_from_url(id_or_url.clone()).unwrap_or(id_or_url);
-    let assignable = target_process::get_assignable(id).await?;
+    let assignable = target_process::get_assignable(tp, id).await?;
 
     println!("{}", assignable.get_branch());
 
@@ -1,11 +1,12 @@
 use crate::utils;
 use color_eyre::Result;
+use target_process::TargetProcessClient;
 
-pub async fn link(id_or_url: Option<String>) -> Result<()> {
+pub async fn link(tp: &TargetProcessClient, id_or_url: Option<String>) -> Result<()> {
     let id = utils::extract_id(id_or_url).await?;
-    let assignable = target_process::get_assignable(id).await?;
+    let assignable = target_process::get_assignable(tp, id).await?;
 
-    println!("{}", assignable.get_link());
+    println!("{}", tp.entity_url(assignable.id));
 
     Ok(())
 }
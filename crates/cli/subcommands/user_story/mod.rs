@@ -1,12 +1,18 @@
+mod attachments;
+mod changelog;
 mod generate_commit;
 mod get_branch;
 mod get_id;
 mod link;
+mod tree;
 mod view;
 
+pub use attachments::{attach, attachments, download as download_attachments};
+pub use changelog::changelog;
 pub use generate_commit::generate_commit;
 pub use get_branch::get_branch;
 pub use get_id::get_id;
 pub use link::link;
+pub use tree::tree;
 pub use view::view;
 
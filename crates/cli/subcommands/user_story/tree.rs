@@ -0,0 +1,55 @@
+use color_eyre::{eyre::eyre, Result};
+use colored::*;
+use target_process::{models::v2::hierarchy::TreeNode, TargetProcessClient};
+
+use crate::utils;
+
+pub async fn tree(tp: &TargetProcessClient, id_or_url: Option<String>, json: bool) -> Result<()> {
+    let id = utils::extract_id(id_or_url).await?;
+    let id: usize = id.parse().map_err(|_| eyre!("Invalid ticket id: {id}"))?;
+
+    let root = target_process::get_ticket_tree(tp, id).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&root)?);
+        return Ok(());
+    }
+
+    print_node(&root, id, "", true, true);
+
+    Ok(())
+}
+
+fn print_node(node: &TreeNode, current_id: usize, prefix: &str, is_last: bool, is_root: bool) {
+    let item = &node.item;
+    let state = item
+        .entity_state
+        .as_ref()
+        .map(|s| format!("[{}]", s.name))
+        .unwrap_or_default();
+
+    let label = format!("{} #{} {}", item.resource_type, item.id, item.name);
+    let label = match item.id == current_id {
+        true => format!("{} {}", label.bold().green(), "← you are here".dimmed()),
+        false => label,
+    };
+
+    let connector = match (is_root, is_last) {
+        (true, _) => "",
+        (false, true) => "└── ",
+        (false, false) => "├── ",
+    };
+
+    println!("{prefix}{connector}{label} {}", state.yellow());
+
+    let child_prefix = match (is_root, is_last) {
+        (true, _) => prefix.to_string(),
+        (false, true) => format!("{prefix}    "),
+        (false, false) => format!("{prefix}│   "),
+    };
+
+    for (i, child) in node.children.iter().enumerate() {
+        let is_last_child = i == node.children.len() - 1;
+        print_node(child, current_id, &child_prefix, is_last_child, false);
+    }
+}
@@ -1,15 +1,22 @@
 use color_eyre::eyre::Result;
+use colored::*;
 use commands::spawn_command;
 use mdka::from_html;
+use target_process::{models::assignable::Assignable, TargetProcessClient};
 
 use crate::utils;
 
-pub async fn view(id_or_url: Option<String>, json: bool, web: bool) -> Result<()> {
+pub async fn view(
+    tp: &TargetProcessClient,
+    id_or_url: Option<String>,
+    json: bool,
+    web: bool,
+) -> Result<()> {
     let id = utils::extract_id(id_or_url).await?;
-    let assignable = target_process::get_assignable(id).await?;
+    let assignable = target_process::get_assignable(tp, id).await?;
 
     if web {
-        spawn_command!("open", assignable.get_link())?;
+        spawn_command!("open", tp.entity_url(assignable.id))?;
         return Ok(());
     }
 
@@ -24,6 +31,9 @@ pub async fn view(id_or_url: Option<String>, json: bool, web: bool) -> Result<()
     println!("===================");
     println!();
 
+    print_ticket_header(&assignable);
+    println!();
+
     match assignable.description {
         Some(description) => print_ticket_body(description),
         None => println!("no description provided."),
@@ -44,3 +54,72 @@ fn print_ticket_body(description: String) {
 
     termimad::print_text(&description.replace("<!--markdown-->", ""));
 }
+
+fn print_ticket_header(assignable: &Assignable) {
+    let mut rows: Vec<(&str, String)> = vec![
+        ("ID", assignable.id.to_string()),
+        ("Type", assignable.entity_type.name.clone()),
+        ("State", assignable.entity_state.name.clone()),
+    ];
+
+    if let Some(project) = &assignable.project {
+        rows.push(("Project", project.name.clone()));
+    }
+
+    let assigned = assignable.get_assigned_users();
+    if !assigned.is_empty() {
+        rows.push(("Assigned", assigned.join(", ")));
+    }
+
+    if let Some(effort) = assignable.effort {
+        let mut value = format!("{effort}pt");
+
+        if let (Some(spent), Some(remain)) = (assignable.time_spent, assignable.time_remain) {
+            value += &format!(" (spent {spent}h, remaining {remain}h)");
+        }
+
+        rows.push(("Effort", value));
+    }
+
+    if let Some(iteration) = &assignable.team_iteration {
+        rows.push(("Iteration", iteration.name.clone()));
+    }
+
+    if let Some(release) = &assignable.release {
+        rows.push(("Release", release.name.clone()));
+    }
+
+    if let Some(feature) = &assignable.feature {
+        rows.push(("Feature", format!("#{} {}", feature.id, feature.name)));
+
+        if let Some(epic) = &feature.epic {
+            rows.push(("Epic", format!("#{} {}", epic.id, epic.name)));
+        }
+    }
+
+    if let Some(priority) = &assignable.priority {
+        rows.push(("Priority", priority.name.clone()));
+    }
+
+    let tags = assignable.get_tags();
+    if !tags.is_empty() {
+        rows.push(("Tags", tags.join(", ")));
+    }
+
+    for field in assignable.custom_fields.iter().flatten() {
+        if let Some(value) = field.display_value() {
+            rows.push((&field.name, value));
+        }
+    }
+
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    for (label, value) in rows {
+        let label = format!("{:width$}", format!("{label}:"));
+        println!("{}  {}", label.bold(), value.yellow());
+    }
+}
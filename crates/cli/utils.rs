@@ -1,30 +1,55 @@
+use std::{
+    collections::HashMap,
+    io::{IsTerminal, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use ai::{models::ChatPayload, ChatProvider, ProviderKind};
 use cached::proc_macro::cached;
-use color_eyre::{eyre::OptionExt, Result};
+use color_eyre::{
+    eyre::{eyre, OptionExt},
+    Result,
+};
+use colored::*;
 use commands::git;
-use config::Config;
+use config::{
+    prompts::{self, PromptKind},
+    secrets::Secret,
+    Config, Origin, ProjectConfig, Setting,
+};
 use inquire::Text;
 use regex::Regex;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use target_process::{models::assignable::Assignable, TargetProcessClient};
 
 #[cached]
 pub(crate) fn build_pr_link(region: String, repository: String, id: String) -> String {
     format!("https://{region}.console.aws.amazon.com/codesuite/codecommit/repositories/{repository}/pull-requests/{id}/details")
 }
 
-pub(crate) async fn get_repository() -> Result<String> {
-    let remote = git::get_remote_url("origin").await?;
+pub(crate) async fn get_repository(remote: &str) -> Result<String> {
+    let url = git::get_remote_url(remote).await?;
+
+    repository_name(&url).ok_or_eyre(format!("unable to extract repository from {remote}"))
+}
 
-    remote
+/// Repository name of a remote url, without the `.git` suffix
+fn repository_name(url: &str) -> Option<String> {
+    url.trim()
+        .trim_end_matches('/')
         .split('/')
-        .last()
-        .map(|s| s.to_string())
-        .ok_or_eyre("unable to extract repository from origin")
+        .next_back()
+        .map(|name| name.trim_end_matches(".git").to_string())
+        .filter(|name| !name.is_empty())
 }
 
 pub(crate) fn branch_to_title(branch: String) -> String {
     let re = Regex::new(r#"\d+"#).unwrap();
 
     let sanitized_branch_title = re
-        .replace(branch.split('/').last().unwrap_or(&branch), "")
+        .replace(branch.split('/').next_back().unwrap_or(&branch), "")
         .replace('_', " ")
         .trim()
         .to_string();
@@ -42,18 +67,47 @@ pub(crate) fn get_ticket_id_from_branch(branch: String) -> Option<String> {
     captures.get(1).map(|s| s.as_str().to_string())
 }
 
-pub(crate) async fn grab_title(title: Option<String>, branch: String) -> Result<String> {
+/// Ticket ids referenced by a commit message, through branch names
+/// (`Merge branch 'feature/1234_foo'`) or conventional commit scopes (`feat(1234): ...`)
+pub(crate) fn get_ticket_ids_from_message(message: &str) -> Vec<String> {
+    let branch_re = Regex::new(r#"[\w-]+/\d+_[\w-]*"#).unwrap();
+    let scope_re = Regex::new(r#"(?m)^\w+\((\d+)\)!?:"#).unwrap();
+
+    let from_branches = branch_re
+        .find_iter(message)
+        .filter_map(|m| get_ticket_id_from_branch(m.as_str().to_string()));
+
+    let from_scopes = scope_re
+        .captures_iter(message)
+        .filter_map(|c| c.get(1).map(|s| s.as_str().to_string()));
+
+    let mut ids: Vec<String> = vec![];
+
+    for id in from_branches.chain(from_scopes) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    ids
+}
+
+pub(crate) async fn grab_title(
+    tp: &TargetProcessClient,
+    title: Option<String>,
+    branch: String,
+) -> Result<String> {
     match title {
         Some(title) => Ok(title),
         None => {
-            if !target_process::has_token() {
+            if !tp.has_token() {
                 return Ok(branch_to_title(branch));
             }
 
             let id =
                 get_ticket_id_from_branch(branch).ok_or_eyre("failed to retrive user_story ID")?;
 
-            let assignable = target_process::get_assignable(id).await?;
+            let assignable = target_process::get_assignable(tp, id).await?;
 
             Ok(assignable.name)
         }
@@ -84,33 +138,340 @@ pub(crate) async fn extract_id(id_or_url: Option<String>) -> Result<String> {
     }
 }
 
-pub(crate) fn get_groq_api_key(config: &Config) -> Result<String> {
-    config
-        .clone()
-        .groq_api_key
-        .or_else(ai::groq::models::get_apikey_from_env)
-        .ok_or_eyre("No api key. Please provide a groq api key in order to use this feature")
+/// Open `path` with `$VISUAL` / `$EDITOR` (`vi` when unset) and wait for it to exit
+pub(crate) async fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or("vi".to_string());
+
+    let status = tokio::process::Command::new(&editor)
+        .arg(path)
+        .status()
+        .await?;
+
+    if !status.success() {
+        return Err(eyre!("{editor} exited with {status}"));
+    }
+
+    Ok(())
+}
+
+/// Provider configured in the profile or the global config, defaults to Groq
+pub(crate) fn get_ai_provider(config: &Config) -> Result<ProviderKind> {
+    match Setting::AiProvider.value(None, None, config) {
+        None => Ok(ProviderKind::default()),
+        Some(p) => ProviderKind::from_str(&p).map_err(|_| eyre!("Invalid ai provider `{p}`")),
+    }
+}
+
+/// Custom endpoint of the AI provider
+pub(crate) fn get_ai_base_url(config: &Config) -> Option<String> {
+    Setting::AiBaseUrl.value(None, None, config)
+}
+
+/// TargetProcess client from the layered config, `None` when the url or the token is missing
+pub(crate) fn target_process_client(
+    project: Option<&ProjectConfig>,
+    config: &Config,
+) -> Result<Option<TargetProcessClient>> {
+    let credentials = target_process_credentials(project, config, |name| std::env::var(name).ok())?;
+
+    Ok(credentials.map(|(url, token)| TargetProcessClient::new(&url, &token)))
+}
+
+/// TargetProcess url and token, both from the same layer so a token is never sent to
+/// another instance
+fn target_process_credentials(
+    project: Option<&ProjectConfig>,
+    config: &Config,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Option<(String, String)>> {
+    let env = |name: &str| env(name).filter(|v| !v.trim().is_empty());
+    let env_token = env(target_process::TOKEN_ENV_NAME);
+
+    // an env token belongs to an env url, even when the selected profile overrides it
+    let env_url = std::iter::once(Setting::TpUrl.env_name())
+        .chain(Setting::TpUrl.legacy_env_name().map(String::from))
+        .any(|name| env(&name).is_some());
+
+    let Some(url) = Setting::TpUrl.resolve_with(None, env, project, config) else {
+        return Ok(None);
+    };
+
+    if env_token.is_some() && !env_url {
+        return Err(eyre!(
+            "${} is set but the TargetProcess url comes from the {}, set ${} as well or unset the token",
+            target_process::TOKEN_ENV_NAME,
+            url.origin,
+            target_process::ENV_NAME
+        ));
+    }
+
+    let token = match &url.origin {
+        Origin::Env(_) => env_token,
+        Origin::Profile(_) => config
+            .profile()
+            .and_then(|(_, profile)| profile.tp_apikey.clone()),
+        _ => config.tp_apikey.clone(),
+    };
+
+    Ok(token
+        .filter(|t| !t.trim().is_empty())
+        .map(|token| (url.value, token)))
+}
+
+pub(crate) fn get_ai_api_key(config: &Config, provider: ProviderKind) -> Option<String> {
+    match provider {
+        ProviderKind::Groq => config.secret(Secret::GroqApiKey),
+        _ => config.secret(Secret::AiApiKey),
+    }
+    .or_else(|| provider.get_apikey_from_env())
 }
 
-pub(crate) fn get_groq_api_key_or_prompt(config: &mut Config) -> Result<String> {
-    match get_groq_api_key(config) {
-        Ok(k) => Ok(k),
-        Err(e) => match Text::new("Enter your Groq API key:")
-            .with_placeholder("gsk_XX")
-            .prompt_skippable()?
-        {
-            None => return Err(e),
-            Some(k) => {
-                config.update_groq_api_key(&k);
-                config.write()?;
-                Ok(k)
+/// Build the configured AI client, asking for the api key when missing
+pub(crate) fn get_ai_client_or_prompt(
+    config: &mut Config,
+) -> Result<(ProviderKind, Box<dyn ChatProvider>)> {
+    let provider = get_ai_provider(config)?;
+    let base_url = get_ai_base_url(config);
+
+    let api_key = match get_ai_api_key(config, provider) {
+        Some(k) => Some(k),
+        None if !provider.requires_api_key() => None,
+        None => {
+            let key = Text::new(&format!("Enter your {provider} API key:"))
+                .prompt_skippable()?
+                .ok_or_eyre(format!(
+                    "No api key. Please provide a {provider} api key in order to use this feature"
+                ))?;
+
+            match provider {
+                ProviderKind::Groq => config.update_groq_api_key(&key),
+                _ => config.update_ai_api_key(&key),
             }
-        },
+            config.write()?;
+
+            Some(key)
+        }
+    };
+
+    let client = ai::provider::new(provider, api_key.as_deref(), base_url.as_deref())?;
+
+    Ok((provider, client))
+}
+
+/// Request a JSON response rendering the tokens on stderr as they arrive
+///
+/// Returns `None` when the user cancels with Ctrl-C
+pub(crate) async fn chat_json_live<T>(
+    provider: &dyn ChatProvider,
+    payload: ChatPayload,
+) -> Result<Option<T>>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let live = std::io::stderr().is_terminal();
+
+    let request = ai::chat_json_stream::<T>(provider, payload, |token| {
+        if live {
+            eprint!("{}", token.dimmed());
+            let _ = std::io::stderr().flush();
+        }
+    });
+
+    let result = tokio::select! {
+        result = request => result.map(Some),
+        _ = tokio::signal::ctrl_c() => Ok(None),
+    };
+
+    if live {
+        eprintln!();
+    }
+
+    if let Ok(None) = result {
+        eprintln!("{}", "Cancelled".yellow());
     }
+
+    result
+}
+
+/// Variables available to the AI prompt templates
+pub(crate) fn prompt_variables(
+    assignable: &Assignable,
+    diff: Option<String>,
+) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("id", assignable.id.to_string()),
+        ("title", assignable.name.clone()),
+        ("type", assignable.entity_type.name.clone()),
+        (
+            "description",
+            assignable
+                .description
+                .clone()
+                .unwrap_or("No description provided".to_string()),
+        ),
+        (
+            "feature",
+            assignable
+                .feature
+                .as_ref()
+                .map(|f| f.name.clone())
+                .unwrap_or_default(),
+        ),
+        ("tags", assignable.get_tags().join(", ")),
+        ("diff", diff.unwrap_or_default()),
+    ])
+}
+
+/// Render the template configured for `kind` (project > config dir > default)
+pub(crate) async fn render_prompt(kind: PromptKind, variables: &HashMap<&str, String>) -> String {
+    let project = ProjectConfig::read().await;
+    let (template, _) = kind.template(project.as_ref());
+
+    prompts::render(&template, variables)
+}
+
+/// Maximum size of the diff sent to the AI, roughly 3k tokens
+pub(crate) const MAX_DIFF_CHARS: usize = 12_000;
+const MAX_FILE_DIFF_CHARS: usize = 3_000;
+
+/// Files where the content is noise for the model
+const SUMMARY_ONLY_FILES: [&str; 5] = [
+    ".lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    ".min.js",
+    ".snap",
+];
+
+/// Shrink a `git diff` to fit the model context
+///
+/// Always lists every changed file with its added/removed lines, then includes
+/// the per-file diffs (each truncated) until `max_chars` is reached
+pub(crate) fn summarize_diff(diff: &str, max_chars: usize) -> String {
+    let files = diff
+        .split("\ndiff --git ")
+        .map(|f| f.trim_start_matches("diff --git "))
+        .filter(|f| !f.trim().is_empty())
+        .collect::<Vec<_>>();
+
+    let mut summary = String::from("Changed files:\n");
+    let mut chunks = String::new();
+    let mut omitted = 0;
+
+    for file in files {
+        let header = file.lines().next().unwrap_or_default();
+        let path = header.rsplit_once(" b/").map_or(header, |(_, p)| p);
+
+        let (added, removed) =
+            file.lines()
+                .fold((0, 0), |(a, r), line| match line.chars().next() {
+                    Some('+') if !line.starts_with("+++") => (a + 1, r),
+                    Some('-') if !line.starts_with("---") => (a, r + 1),
+                    _ => (a, r),
+                });
+
+        summary += &format!("- {path} (+{added} -{removed})\n");
+
+        if SUMMARY_ONLY_FILES.iter().any(|s| path.ends_with(s)) {
+            continue;
+        }
+
+        let mut chunk = format!("diff --git {file}");
+
+        if chunk.len() > MAX_FILE_DIFF_CHARS {
+            let end = chunk
+                .char_indices()
+                .take_while(|(i, _)| *i < MAX_FILE_DIFF_CHARS)
+                .filter(|(_, c)| *c == '\n')
+                .last()
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+
+            chunk.truncate(end);
+            chunk += "\n... (truncated)";
+        }
+
+        if summary.len() + chunks.len() + chunk.len() > max_chars {
+            omitted += 1;
+            continue;
+        }
+
+        chunks += &chunk;
+        chunks += "\n";
+    }
+
+    if omitted > 0 {
+        summary += &format!("({omitted} file diffs omitted to fit the context)\n");
+    }
+
+    format!("{summary}\n{chunks}").trim_end().to_string()
 }
 
 #[cfg(test)]
 mod test {
+    use config::{Config, Profile};
+
+    fn config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "tp_url": "https://global.tpondemand.com",
+            "tp_apikey": "global",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn target_process_credentials() {
+        let no_env = |_: &str| None;
+        let env_token =
+            |name: &str| (name == target_process::TOKEN_ENV_NAME).then(|| "env".to_string());
+        let env_both = |name: &str| match name {
+            "TARGET_PROCESS_API_BASE_URL" => Some("https://env.tpondemand.com".to_string()),
+            name if name == target_process::TOKEN_ENV_NAME => Some("env".to_string()),
+            _ => None,
+        };
+
+        let mut config = config();
+
+        assert_eq!(
+            super::target_process_credentials(None, &config, no_env).unwrap(),
+            Some(("https://global.tpondemand.com".into(), "global".into()))
+        );
+        assert_eq!(
+            super::target_process_credentials(None, &config, env_both).unwrap(),
+            Some(("https://env.tpondemand.com".into(), "env".into()))
+        );
+        // the env token would be sent to the global instance
+        assert!(super::target_process_credentials(None, &config, env_token).is_err());
+
+        config.profiles.insert(
+            "acme".into(),
+            Profile {
+                tp_url: Some("https://acme.tpondemand.com".into()),
+                tp_apikey: Some("acme".into()),
+                ..Default::default()
+            },
+        );
+        config.active_profile = Some("acme".into());
+
+        assert_eq!(
+            super::target_process_credentials(None, &config, env_both).unwrap(),
+            Some(("https://acme.tpondemand.com".into(), "acme".into()))
+        );
+
+        // a profile without its own token never gets the global one
+        config.profiles.get_mut("acme").unwrap().tp_apikey = None;
+        assert_eq!(
+            super::target_process_credentials(None, &config, no_env).unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn extract_id() {
         let url = "https://company.tpondemand.com/entity/125371-show-current-and-previous-month-in"
@@ -121,6 +482,26 @@ mod test {
         assert_eq!(result, Some("125371".to_string()));
     }
 
+    #[test]
+    fn repository_name() {
+        for url in [
+            "https://git-codecommit.eu-west-1.amazonaws.com/v1/repos/web.git",
+            "ssh://git-codecommit.eu-west-1.amazonaws.com/v1/repos/web\n",
+            "codecommit://web",
+        ] {
+            assert_eq!(super::repository_name(url).as_deref(), Some("web"), "{url}");
+        }
+
+        assert_eq!(
+            super::repository_name("https://example.com/repos/my.github.io.git").as_deref(),
+            Some("my.github.io")
+        );
+        assert_eq!(
+            super::repository_name("https://example.com/"),
+            Some("example.com".into())
+        );
+    }
+
     #[test]
     fn branch_to_title() {
         let string = "feature/115068_translate_report_type_payout_transactions".to_string();
@@ -141,12 +522,43 @@ mod test {
         assert_eq!(output, Some("115068".into()))
     }
 
+    #[test]
+    fn extract_ids_from_commit_message() {
+        let merge = "Merge branch 'feature/115068_translate_report' into develop";
+        assert_eq!(super::get_ticket_ids_from_message(merge), vec!["115068"]);
+
+        let conventional = "feat(120890): add payouts\n\nSee feature/120890_payouts";
+        assert_eq!(
+            super::get_ticket_ids_from_message(conventional),
+            vec!["120890"]
+        );
+
+        assert!(super::get_ticket_ids_from_message("chore: bump deps").is_empty());
+    }
+
     #[tokio::test]
     async fn grab_title_should_not_execute_async_code_if_title_is_given() {
-        let data = super::grab_title(Some("demo".to_string()), "feature/120890_abc".to_string())
+        let tp = target_process::TargetProcessClient::new("https://acme.tpondemand.com", "token");
+        let data = super::grab_title(&tp, Some("demo".to_string()), "feature/120890_abc".into())
             .await
             .unwrap();
 
         assert_eq!("demo".to_string(), data);
     }
+
+    #[test]
+    fn summarize_diff() {
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1,2 @@\n-old\n+new\n+line\ndiff --git a/Cargo.lock b/Cargo.lock\n--- a/Cargo.lock\n+++ b/Cargo.lock\n+dep\n";
+
+        let summary = super::summarize_diff(diff, super::MAX_DIFF_CHARS);
+
+        assert!(summary.contains("- src/main.rs (+2 -1)"));
+        assert!(summary.contains("- Cargo.lock (+1 -0)"));
+        assert!(summary.contains("+new"));
+        assert!(!summary.contains("+dep"));
+
+        let summary = super::summarize_diff(diff, 60);
+        assert!(summary.contains("(1 file diffs omitted to fit the context)"));
+        assert!(!summary.contains("+new"));
+    }
 }
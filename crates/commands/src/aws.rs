@@ -27,6 +27,9 @@ pub struct PullRequest {
 
     #[serde(rename = "pullRequestTargets")]
     pub targets: Vec<PullRequestTarget>,
+
+    /// Needed to approve the current revision
+    pub revision_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -376,8 +379,67 @@ pub async fn merge_pr_by_squash(
     Err(CommandError::IOError(raw_stderr))
 }
 
-pub async fn start_pipeline_execution(name: String, profile: String) -> Result<()> {
-    command!(
+pub async fn approve_pull_request(id: String, revision_id: String, profile: String) -> Result<()> {
+    let output = command!(
+        "aws",
+        "codecommit",
+        "update-pull-request-approval-state",
+        "--pull-request-id",
+        &id,
+        "--revision-id",
+        &revision_id,
+        "--approval-state",
+        "APPROVE",
+        "--profile",
+        &profile,
+        "--color",
+        "off"
+    )
+    .output()
+    .await
+    .map_err(CommandError::from_io)?;
+
+    if !output.status.success() {
+        return Err(CommandError::IOError(String::from_utf8(output.stderr)?));
+    }
+
+    Ok(())
+}
+
+pub async fn close_pull_request(id: String, profile: String) -> Result<PullRequest> {
+    let output = command!(
+        "aws",
+        "codecommit",
+        "update-pull-request-status",
+        "--pull-request-id",
+        &id,
+        "--pull-request-status",
+        "CLOSED",
+        "--profile",
+        &profile,
+        "--color",
+        "off",
+        "--output",
+        "json"
+    )
+    .output()
+    .await
+    .map_err(CommandError::from_io)?;
+
+    if !output.status.success() {
+        return Err(CommandError::IOError(String::from_utf8(output.stderr)?));
+    }
+
+    let raw_stdout = String::from_utf8(output.stdout)?;
+
+    serde_json::from_str::<PullRequestResponse>(&raw_stdout)
+        .map(|r| r.pull_request)
+        .map_err(CommandError::from_serde)
+}
+
+/// Returns the id of the started execution
+pub async fn start_pipeline_execution(name: String, profile: String) -> Result<Option<String>> {
+    let stdout = command!(
         "aws",
         "codepipeline",
         "start-pipeline-execution",
@@ -392,7 +454,12 @@ pub async fn start_pipeline_execution(name: String, profile: String) -> Result<(
     )
     .output()
     .await
-    .map_err(CommandError::from_io)?;
+    .map_err(CommandError::from_io)?
+    .stdout;
 
-    Ok(())
+    let raw_stdout = String::from_utf8(stdout)?;
+
+    Ok(serde_json::from_str::<serde_json::Value>(&raw_stdout)
+        .ok()
+        .and_then(|v| v["pipelineExecutionId"].as_str().map(String::from)))
 }
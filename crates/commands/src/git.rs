@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use crate::command;
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
 
 #[derive(Clone)]
 pub struct Branch(pub String);
@@ -111,6 +111,87 @@ pub async fn config(key: String) -> Result<String> {
     Ok(out.to_string())
 }
 
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub hash: String,
+
+    /// Full commit message (subject and body)
+    pub message: String,
+}
+
+/// Commits reachable from the end of `range` (e.g. `v1.0.0..v1.1.0`) but not from its start
+pub async fn log(range: &str) -> Result<Vec<Commit>> {
+    let output = command!("git", "log", "--format=%H%x1f%B%x1e", range)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(eyre!(
+            "git log failed: {}",
+            String::from_utf8(output.stderr)?.trim()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .split('\x1e')
+        .filter_map(|record| {
+            let (hash, message) = record.trim().split_once('\x1f')?;
+
+            Some(Commit {
+                hash: hash.to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Changes staged for the next commit
+pub async fn staged_diff() -> Result<String> {
+    let output = command!("git", "diff", "--cached", "--no-color", "--no-ext-diff")
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(eyre!(
+            "git diff failed: {}",
+            String::from_utf8(output.stderr)?.trim()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Commit the staged changes, running the hooks in the foreground
+pub async fn commit(message: &str, description: Option<&str>) -> Result<()> {
+    let mut args = vec!["commit", "-m", message];
+
+    if let Some(description) = description {
+        args.extend(["-m", description]);
+    }
+
+    let status = command!("git").args(args).spawn()?.wait().await?;
+
+    if !status.success() {
+        return Err(eyre!("git commit failed ({status})"));
+    }
+
+    Ok(())
+}
+
+/// All tags, newest version first
+pub async fn tags() -> Result<Vec<String>> {
+    let stdout = command!("git", "tag", "--list", "--sort=-v:refname")
+        .output()
+        .await?
+        .stdout;
+
+    Ok(String::from_utf8(stdout)?
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
 pub async fn fetch(prune: bool) -> Result<()> {
     if prune {
         command!("git", "fetch", "--prune").output().await?;
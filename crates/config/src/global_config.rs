@@ -3,9 +3,11 @@ use color_eyre::eyre::OptionExt;
 use color_eyre::{eyre::eyre, Result};
 use serde::{Deserialize, Serialize};
 use std::env::var as env_var;
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 use tokio::io::AsyncReadExt;
 
+use crate::{secrets, Hook, Profile, SlackConfig};
+
 pub(crate) fn dir() -> Option<PathBuf> {
     let user_directories = directories::UserDirs::new()?;
     let home = user_directories.home_dir().to_str()?;
@@ -19,6 +21,14 @@ pub(crate) fn dir() -> Option<PathBuf> {
     )
 }
 
+/// Directory for files managed by tpaws next to the config (`<config dir>/tpaws`)
+pub fn data_dir() -> Result<PathBuf> {
+    let config = dir().ok_or_eyre("unable to get config_dir")?;
+    let parent = config.parent().ok_or_eyre("unable to get config_dir")?;
+
+    Ok(parent.join("tpaws"))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub username: String,
@@ -29,17 +39,68 @@ pub struct Config {
     pub arn: Option<String>,
     pub groq_api_key: Option<String>,
     pub ai_model: Option<String>,
+    /// One of `groq`, `openai` or `anthropic`, defaults to `groq`
+    pub ai_provider: Option<String>,
+    /// Override the provider endpoint, e.g. a local OpenAI-compatible server
+    pub ai_base_url: Option<String>,
+    /// Api key for non-Groq providers
+    pub ai_api_key: Option<String>,
     pub tp_apikey: Option<String>,
     pub tp_url: Option<String>,
+    pub slack: Option<SlackConfig>,
+
+    /// Defaults used when neither a flag, an env variable nor `tpaws.json` sets them,
+    /// see [`crate::Setting`]
+    pub project: Option<String>,
+    pub base_branch: Option<String>,
+    pub aws_profile: Option<String>,
+    pub remote: Option<String>,
+
+    /// Per client settings, see [`Profile`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Profile used outside of the repositories matched by a profile, see `tpaws config use`
+    pub default_profile: Option<String>,
+
+    /// Hooks of every repository, the only place `command` hooks are run from
+    pub hooks: Option<Vec<Hook>>,
+
+    /// Profile selected for this run, see [`Config::select_profile`]
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+
+    /// Whether the secrets were read from the store: only then does a missing secret
+    /// mean it was removed, and gets deleted from the store on write
+    #[serde(skip)]
+    pub secrets_loaded: bool,
 }
 
 impl Config {
+    fn active_profile_mut(&mut self) -> Option<&mut Profile> {
+        self.profiles.get_mut(self.active_profile.as_deref()?)
+    }
+
+    /// The `update_ai_*` methods change the selected profile, if any
     pub fn update_ai_model(&mut self, model: &str) {
-        self.ai_model = Some(model.to_string());
+        match self.active_profile_mut() {
+            Some(profile) => profile.ai_model = Some(model.to_string()),
+            None => self.ai_model = Some(model.to_string()),
+        }
     }
 
     pub fn update_groq_api_key(&mut self, key: &str) {
-        self.groq_api_key = Some(key.to_string());
+        match self.active_profile_mut() {
+            Some(profile) => profile.groq_api_key = Some(key.to_string()),
+            None => self.groq_api_key = Some(key.to_string()),
+        }
+    }
+
+    pub fn update_ai_api_key(&mut self, key: &str) {
+        match self.active_profile_mut() {
+            Some(profile) => profile.ai_api_key = Some(key.to_string()),
+            None => self.ai_api_key = Some(key.to_string()),
+        }
     }
 
     pub fn update_auth(&mut self, arn: String) {
@@ -57,6 +118,11 @@ impl Config {
         true
     }
 
+    /// Location of the global config file
+    pub fn path() -> Result<PathBuf> {
+        dir().ok_or_eyre("unable to get config_dir")
+    }
+
     pub fn is_first_run() -> Result<bool> {
         let path = dir().ok_or_eyre("unable to get config_dir")?;
 
@@ -65,12 +131,48 @@ impl Config {
 }
 
 /// FS Methods
+///
+/// Secrets are kept in the [`secrets::store`], the file only holds the other settings
 impl Config {
     /// Read from file-system
     pub fn write(&self) -> Result<()>
     where
         Self: Sized + serde::de::DeserializeOwned + serde::Serialize,
     {
+        let store = secrets::store()?;
+        let mut plain = self.clone();
+        plain.save_secrets(store.as_ref())?;
+
+        // the file on disk is the previous version, its removed profiles take their secrets
+        if let Some(previous) = Self::read_file() {
+            plain.delete_removed_profile_secrets(&previous, store.as_ref())?;
+        }
+
+        plain.write_file()
+    }
+
+    /// Delete the stored secrets of the profiles removed since `previous`, for changes
+    /// made to the file directly (`tpaws config edit`)
+    pub fn forget_removed_profiles(&self, previous: &Config) -> Result<()> {
+        if previous
+            .profiles
+            .keys()
+            .all(|name| self.profiles.contains_key(name))
+        {
+            return Ok(());
+        }
+
+        self.delete_removed_profile_secrets(previous, secrets::store()?.as_ref())
+    }
+
+    /// Current file content, without the secrets
+    fn read_file() -> Option<Self> {
+        let content = std::fs::read_to_string(dir()?).ok()?;
+
+        serde_json::from_str(&content).ok()
+    }
+
+    fn write_file(&self) -> Result<()> {
         let path = dir().ok_or_eyre("unable to get config_dir")?;
         let file = std::fs::File::create(path.clone())?;
 
@@ -94,6 +196,29 @@ impl Config {
         let mut contents = String::new();
         file.read_to_string(&mut contents).await?;
 
-        Ok(serde_json::from_str(&contents)?)
+        let mut config: Config = serde_json::from_str(&contents)?;
+
+        let store = match secrets::store() {
+            Ok(store) => store,
+            Err(e) => {
+                eprintln!("Unable to open the secret store: {e}");
+                return Ok(config);
+            }
+        };
+
+        if config.has_plaintext_secrets() {
+            let mut plain = config.clone();
+
+            match plain.save_secrets(store.as_ref()) {
+                Ok(()) => plain.write_file()?,
+                Err(e) => eprintln!("Unable to move the secrets to the {}: {e}", store.name()),
+            }
+        }
+
+        if let Err(e) = config.load_secrets(store.as_ref()) {
+            eprintln!("Unable to read the secrets from the {}: {e}", store.name());
+        }
+
+        Ok(config)
     }
 }
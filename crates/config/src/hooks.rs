@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A destination for tpaws events, configured under `hooks` in `tpaws.json`
+/// or in the global config
+///
+/// ```json
+/// { "events": ["pr.created"], "type": "teams", "webhook_url": "https://..." }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hook {
+    /// Event names delivered to this sink (`pr.created`, `release.*`), all when empty
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    #[serde(flatten)]
+    pub sink: Sink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Sink {
+    /// Posts to the configured Slack, `channel` and `webhook_url` override it,
+    /// `tpaws.json` hooks need their own `webhook_url`
+    Slack {
+        webhook_url: Option<String>,
+        channel: Option<String>,
+    },
+
+    /// Microsoft Teams incoming webhook
+    Teams { webhook_url: String },
+
+    /// JSON POST, `body` is a template with `{{event}}`, `{{summary}}`, `{{link}}`
+    /// and the event fields; the whole event is sent when missing
+    Webhook {
+        url: String,
+        body: Option<String>,
+
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+
+    /// Shell command receiving the event as JSON on stdin and `TPAWS_*` variables,
+    /// only from the global config: `tpaws.json` comes with the repository
+    Command { command: String },
+}
+
+impl Hook {
+    /// `events` entries match exactly or by prefix with a trailing `*`
+    pub fn accepts(&self, event: &str) -> bool {
+        self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => event.starts_with(prefix),
+                    None => pattern == event,
+                })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(events: &[&str]) -> Hook {
+        Hook {
+            events: events.iter().map(|e| e.to_string()).collect(),
+            sink: Sink::Command {
+                command: "true".into(),
+            },
+        }
+    }
+
+    #[test]
+    fn event_filter() {
+        assert!(hook(&[]).accepts("pr.created"));
+        assert!(hook(&["pr.created"]).accepts("pr.created"));
+        assert!(!hook(&["pr.created"]).accepts("pr.merged"));
+        assert!(hook(&["pr.*"]).accepts("pr.merged"));
+        assert!(!hook(&["pr.*"]).accepts("release.pushed"));
+    }
+
+    #[test]
+    fn parse_sinks() {
+        let hooks: Vec<Hook> = serde_json::from_str(
+            r##"[
+                { "type": "slack", "channel": "#releases" },
+                { "events": ["pr.*"], "type": "teams", "webhook_url": "https://teams" },
+                { "type": "webhook", "url": "https://ci", "body": "{\"text\": \"{{summary}}\"}" },
+                { "type": "command", "command": "notify-send tpaws" }
+            ]"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hooks[0].sink,
+            Sink::Slack {
+                webhook_url: None,
+                channel: Some("#releases".into())
+            }
+        );
+        assert_eq!(hooks[1].events, vec!["pr.*"]);
+        assert!(matches!(&hooks[2].sink, Sink::Webhook { headers, .. } if headers.is_empty()));
+        assert!(
+            matches!(&hooks[3].sink, Sink::Command { command } if command == "notify-send tpaws")
+        );
+    }
+}
@@ -1,8 +1,16 @@
 mod global_config;
+mod hooks;
+mod profiles;
 mod project_config;
+pub mod prompts;
+pub mod secrets;
+mod settings;
+mod slack;
 pub mod util;
 
 pub use global_config::*;
+pub use hooks::*;
+pub use profiles::*;
 pub use project_config::*;
-
-pub const DEFAULT_AI_MODEL: &str = "llama3-8b-8192";
+pub use settings::*;
+pub use slack::*;
@@ -0,0 +1,238 @@
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{secrets::Secret, Config};
+
+/// Settings of one client, under `profiles.<name>` in the global config
+///
+/// ```json
+/// { "remotes": ["github.com/client-a/"], "tp_url": "https://client-a.tpondemand.com" }
+/// ```
+///
+/// Missing values fall back to the global ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Selects the profile in repositories whose remote url contains one of these,
+    /// compared as `host/org/repo` whatever the url scheme
+    #[serde(default)]
+    pub remotes: Vec<String>,
+
+    pub tp_url: Option<String>,
+    pub tp_apikey: Option<String>,
+
+    /// TargetProcess user of this instance, fetched from the api when missing
+    pub user_id: Option<usize>,
+    pub aws_profile: Option<String>,
+    pub ai_provider: Option<String>,
+    pub ai_base_url: Option<String>,
+    pub ai_api_key: Option<String>,
+    pub groq_api_key: Option<String>,
+    pub ai_model: Option<String>,
+    pub project: Option<String>,
+    pub base_branch: Option<String>,
+}
+
+/// `host/org/repo` for the https, `ssh://` and `git@host:org/repo` forms of a remote url
+fn normalize_remote(url: &str) -> String {
+    let url = url.trim();
+    let (has_scheme, rest) = match url.split_once("://") {
+        Some((_, rest)) => (true, rest),
+        None => (false, url),
+    };
+
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+
+    let host = match host.split_once(':') {
+        // `ssh://host:22/org/repo`
+        Some((host, _port)) if has_scheme => host.to_string(),
+        // `host:org/repo`
+        Some((host, org)) => format!("{host}/{org}"),
+        None => host.to_string(),
+    };
+
+    let normalized = match path.is_empty() && !rest.contains('/') {
+        true => host,
+        false => format!("{host}/{path}"),
+    };
+
+    normalized
+        .strip_suffix(".git")
+        .unwrap_or(&normalized)
+        .to_string()
+}
+
+impl Profile {
+    /// Length of the longest `remotes` entry contained in `url`
+    fn matches(&self, url: &str) -> Option<usize> {
+        let url = normalize_remote(url);
+
+        self.remotes
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .map(|pattern| normalize_remote(pattern))
+            .filter(|pattern| url.contains(pattern.as_str()))
+            .map(|pattern| pattern.len())
+            .max()
+    }
+}
+
+impl Config {
+    /// Profile selected for this run, see [`Config::select_profile`]
+    pub fn profile(&self) -> Option<(&str, &Profile)> {
+        let name = self.active_profile.as_deref()?;
+
+        self.profiles.get(name).map(|profile| (name, profile))
+    }
+
+    /// Pick the profile of this run: `name` (`--profile-set`, then `$TPAWS_PROFILE`),
+    /// the profile matching `remote_url`, then the one chosen with `tpaws config use`
+    pub fn select_profile(&mut self, name: Option<String>, remote_url: Option<&str>) -> Result<()> {
+        let name = name
+            .or_else(|| std::env::var("TPAWS_PROFILE").ok())
+            .filter(|n| !n.is_empty());
+
+        self.active_profile = match name {
+            Some(name) if !self.profiles.contains_key(&name) => {
+                return Err(eyre!("Unknown profile `{name}`"));
+            }
+            Some(name) => Some(name),
+            None => remote_url
+                .and_then(|url| self.matching_profile(url))
+                .map(String::from)
+                .or_else(|| self.default_profile.clone()),
+        };
+
+        Ok(())
+    }
+
+    /// Most specific profile whose `remotes` match `url`
+    pub fn matching_profile(&self, url: &str) -> Option<&str> {
+        self.profiles
+            .iter()
+            .filter_map(|(name, profile)| profile.matches(url).map(|len| (len, name)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Secret of the selected profile, or of the global config
+    pub fn secret(&self, secret: Secret) -> Option<String> {
+        let profile = self.profile().and_then(|(_, profile)| match secret {
+            Secret::GroqApiKey => profile.groq_api_key.clone(),
+            Secret::AiApiKey => profile.ai_api_key.clone(),
+            Secret::TpApikey => profile.tp_apikey.clone(),
+            Secret::SlackBotToken | Secret::SlackWebhookUrl => None,
+        });
+
+        profile.or_else(|| match secret {
+            Secret::GroqApiKey => self.groq_api_key.clone(),
+            Secret::AiApiKey => self.ai_api_key.clone(),
+            Secret::TpApikey => self.tp_apikey.clone(),
+            Secret::SlackBotToken => self.slack.as_ref().and_then(|s| s.bot_token.clone()),
+            Secret::SlackWebhookUrl => self.slack.as_ref().and_then(|s| s.webhook_url.clone()),
+        })
+    }
+
+    /// TargetProcess user id, `None` when the selected profile doesn't know it yet
+    pub fn tp_user_id(&self) -> Option<usize> {
+        match self.profile() {
+            Some((_, profile)) => profile.user_id,
+            None => Some(self.user_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "tp_apikey": "global",
+            "default_profile": "acme",
+            "profiles": {
+                "acme": { "tp_apikey": "acme" },
+                "client-a": { "remotes": ["github.com/client-a/"] },
+                "client-a-api": { "remotes": ["github.com/client-a/api", "gitlab.com"] },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn select_by_remote() {
+        let mut config = config();
+
+        config
+            .select_profile(None, Some("git@github.com:client-a/web.git"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "client-a");
+
+        config
+            .select_profile(None, Some("ssh://git@github.com:22/client-a/api.git"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "client-a-api");
+
+        config
+            .select_profile(None, Some("https://bitbucket.org/client-a/web.git"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "acme");
+
+        config
+            .select_profile(None, Some("https://github.com/client-a/web.git"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "client-a");
+
+        config
+            .select_profile(None, Some("https://github.com/client-a/api.git"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "client-a-api");
+
+        config
+            .select_profile(Some("client-a".into()), Some("https://gitlab.com/x"))
+            .unwrap();
+        assert_eq!(config.profile().unwrap().0, "client-a");
+
+        assert!(config.select_profile(Some("unknown".into()), None).is_err());
+    }
+
+    #[test]
+    fn normalize_remotes() {
+        for url in [
+            "https://github.com/org/repo.git",
+            "https://user@github.com/org/repo",
+            "git@github.com:org/repo.git",
+            "ssh://git@github.com:22/org/repo.git",
+            "github.com/org/repo",
+        ] {
+            assert_eq!(normalize_remote(url), "github.com/org/repo", "{url}");
+        }
+
+        assert_eq!(
+            normalize_remote("github.com/client-a/"),
+            "github.com/client-a/"
+        );
+        assert_eq!(normalize_remote("gitlab.com"), "gitlab.com");
+    }
+
+    #[test]
+    fn profile_overrides() {
+        let mut config = config();
+
+        config.select_profile(Some("acme".into()), None).unwrap();
+        assert_eq!(config.secret(Secret::TpApikey).as_deref(), Some("acme"));
+        assert_eq!(config.tp_user_id(), None);
+
+        config
+            .select_profile(Some("client-a".into()), None)
+            .unwrap();
+        assert_eq!(config.secret(Secret::TpApikey).as_deref(), Some("global"));
+
+        config.active_profile = None;
+        assert_eq!(config.tp_user_id(), Some(1));
+    }
+}
@@ -1,12 +1,34 @@
 use color_eyre::Result;
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 use tokio::io::AsyncReadExt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{Hook, SlackConfig};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectConfig {
     pub name: Option<String>,
+
+    /// TargetProcess releases are named `<release_prefix>@<version>`
+    pub release_prefix: Option<String>,
+
+    /// Overrides the global `ai_model` for this repository
+    pub ai_model: Option<String>,
+
+    /// Target branch of new PRs, defaults to `develop`
+    pub base_branch: Option<String>,
+    pub aws_profile: Option<String>,
+
+    /// Git remote of the repository, defaults to `origin`
+    pub remote: Option<String>,
+
+    /// Prompt templates by name (`commit`, `pull-request`), see `tpaws ai prompts`
+    pub prompts: Option<HashMap<String, String>>,
+    pub slack: Option<SlackConfig>,
+
+    /// Sinks notified of PR, ticket and release events
+    pub hooks: Option<Vec<Hook>>,
 }
 
 const FILE_PATH: &str = "./tpaws.json";
@@ -41,7 +63,25 @@ impl ProjectConfig {
         let mut contents = String::new();
         file.read_to_string(&mut contents).await.ok()?;
 
-        serde_json::from_str(&contents).ok()
+        let mut config: Self = serde_json::from_str(&contents).ok()?;
+
+        // tpaws.json is committed, secrets belong in the global config
+        if config
+            .slack
+            .as_mut()
+            .is_some_and(SlackConfig::remove_secrets)
+        {
+            eprintln!(
+                "Ignoring the Slack webhook_url and bot_token of tpaws.json, set them with `tpaws slack setup`"
+            );
+        }
+
+        Some(config)
+    }
+
+    /// Location of `tpaws.json` in the current directory
+    pub fn path() -> Result<std::path::PathBuf> {
+        Ok(std::env::current_dir()?.join(FILE_PATH.trim_start_matches("./")))
     }
 
     pub fn exists() -> bool {
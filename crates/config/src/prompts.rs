@@ -0,0 +1,228 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use color_eyre::Result;
+
+use crate::{global_config::data_dir, ProjectConfig};
+
+/// Variables available to every template
+pub const VARIABLES: [&str; 7] = [
+    "id",
+    "title",
+    "type",
+    "description",
+    "feature",
+    "tags",
+    "diff",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString, strum::EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum PromptKind {
+    /// Commit message for `ticket generate-commit`
+    Commit,
+
+    /// PR title and description for `pr create --ai`
+    PullRequest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptSource {
+    /// `prompts` in the repository `tpaws.json`
+    Project,
+
+    /// Template file in the config dir
+    Global(PathBuf),
+
+    Default,
+}
+
+impl std::fmt::Display for PromptSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptSource::Project => write!(f, "tpaws.json"),
+            PromptSource::Global(path) => write!(f, "{}", path.display()),
+            PromptSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+impl PromptKind {
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            PromptKind::Commit => DEFAULT_COMMIT_PROMPT,
+            PromptKind::PullRequest => DEFAULT_PULL_REQUEST_PROMPT,
+        }
+    }
+
+    /// `<config dir>/tpaws/prompts/<kind>.md`
+    pub fn path(&self) -> Result<PathBuf> {
+        Ok(data_dir()?.join("prompts").join(format!("{self}.md")))
+    }
+
+    /// Resolve the template: project config > config dir > default
+    pub fn template(&self, project: Option<&ProjectConfig>) -> (String, PromptSource) {
+        let project_template = project
+            .and_then(|p| p.prompts.as_ref())
+            .and_then(|p| p.get(&self.to_string()));
+
+        if let Some(template) = project_template {
+            return (template.clone(), PromptSource::Project);
+        }
+
+        if let Ok(path) = self.path() {
+            if let Ok(template) = std::fs::read_to_string(&path) {
+                return (template, PromptSource::Global(path));
+            }
+        }
+
+        (self.default_template().to_string(), PromptSource::Default)
+    }
+
+    /// Write the default template to the config dir if missing
+    pub fn ensure_file(&self) -> Result<PathBuf> {
+        let path = self.path()?;
+
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::write(&path, self.default_template())?;
+        }
+
+        Ok(path)
+    }
+
+    /// Remove the customized template, going back to the default one
+    pub fn reset(&self) -> Result<()> {
+        let path = self.path()?;
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Replace `{{name}}` placeholders, unknown ones are left untouched
+pub fn render(template: &str, variables: &HashMap<&str, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let placeholder = &rest[start..start + end + 2];
+        let name = placeholder[2..placeholder.len() - 2].trim();
+
+        match variables.get(name) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(placeholder),
+        }
+
+        rest = &rest[start + end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+const DEFAULT_COMMIT_PROMPT: &str = r#"You're an ai specialist, you should know how to generate a commit message.
+Given ID, title and content of a User Story generate a commit message for it.
+
+The format should follow conventional commits.
+    - "feat(<id>): <message>" -> user stories
+    - "fix(<id>): <message>" -> bugs
+
+Return the commit message as response. Just that, no other information is needed.
+On a new line, add a longer description for the commit message if needed.
+
+The commit message must be lowercased and in present tense.
+Don't just copy the title, but provide a meaningful message.
+
+Return a JSON object with the following structure:
+{
+    "message": "feat(123): add new feature",
+    "description": "This feature will allow users to do X and Y" // optional
+}
+
+ID: {{id}}
+Title: {{title}}
+Type: {{type}}
+Feature: {{feature}}
+Tags: {{tags}}
+Description:
+{{description}}
+
+{{diff}}
+"#;
+
+const DEFAULT_PULL_REQUEST_PROMPT: &str = r#"You're an AI created to help us generate a PR description given a UserStory or Bug.
+You will be provided with Title, Description, ID and other details about the UserStory or Bug.
+
+The title must be written in present tense and the description should be a brief summary of the changes.
+Use UserStory/Bug info to improve title and description.
+Don't just copy the title/description, but provide a meaningful content.
+
+Return a json object with the following fields:
+{
+    "title": "PR title",
+    "description": "Pr description"
+}
+
+Return the json data as response. Just that, no other information is needed.
+
+ID: {{id}}
+Title: {{title}}
+Type: {{type}}
+Feature: {{feature}}
+Tags: {{tags}}
+Description:
+{{description}}
+
+{{diff}}
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let variables = HashMap::from([("id", "42".to_string()), ("title", "Login".to_string())]);
+
+        assert_eq!(
+            render("feat({{id}}): {{ title }} {{unknown}}", &variables),
+            "feat(42): Login {{unknown}}"
+        );
+        assert_eq!(render("no vars {{ open", &variables), "no vars {{ open");
+    }
+
+    #[test]
+    fn project_template_wins() {
+        let project = ProjectConfig {
+            name: None,
+            release_prefix: None,
+            ai_model: None,
+            base_branch: None,
+            aws_profile: None,
+            remote: None,
+            slack: None,
+            hooks: None,
+            prompts: Some(HashMap::from([(
+                "commit".to_string(),
+                "custom {{id}}".to_string(),
+            )])),
+        };
+
+        let (template, source) = PromptKind::Commit.template(Some(&project));
+        assert_eq!(template, "custom {{id}}");
+        assert_eq!(source, PromptSource::Project);
+    }
+}
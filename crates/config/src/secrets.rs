@@ -0,0 +1,496 @@
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::{eyre::eyre, Result};
+use rand::RngCore;
+
+use crate::{data_dir, Config, Profile, SlackConfig};
+
+const SERVICE: &str = "tpaws";
+
+/// Credentials kept out of the plaintext config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, strum::EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum Secret {
+    GroqApiKey,
+    AiApiKey,
+    TpApikey,
+    SlackBotToken,
+    SlackWebhookUrl,
+}
+
+impl Secret {
+    fn field(self, config: &mut Config) -> &mut Option<String> {
+        match self {
+            Secret::GroqApiKey => &mut config.groq_api_key,
+            Secret::AiApiKey => &mut config.ai_api_key,
+            Secret::TpApikey => &mut config.tp_apikey,
+            Secret::SlackBotToken => {
+                &mut config.slack.get_or_insert_with(Default::default).bot_token
+            }
+            Secret::SlackWebhookUrl => {
+                &mut config
+                    .slack
+                    .get_or_insert_with(Default::default)
+                    .webhook_url
+            }
+        }
+    }
+
+    /// Slack stays global, the other secrets can be set per profile
+    fn profile_field(self, profile: &mut Profile) -> Option<&mut Option<String>> {
+        match self {
+            Secret::GroqApiKey => Some(&mut profile.groq_api_key),
+            Secret::AiApiKey => Some(&mut profile.ai_api_key),
+            Secret::TpApikey => Some(&mut profile.tp_apikey),
+            Secret::SlackBotToken | Secret::SlackWebhookUrl => None,
+        }
+    }
+}
+
+/// Secrets are stored by key: `tp_apikey`, or `profiles.<name>.tp_apikey` for a profile
+pub trait SecretStore {
+    fn name(&self) -> &'static str;
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    fn set(&self, key: &str, value: &str) -> Result<()>;
+    fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// OS keyring: Secret Service on Linux, Keychain on macOS, Credential Manager on Windows
+pub struct KeyringStore;
+
+/// The keyring backends may drive their own async runtime, keep them off the tokio threads
+fn isolated<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| scope.spawn(f).join().expect("keyring thread panicked"))
+}
+
+impl KeyringStore {
+    /// Whether a keyring service is reachable (e.g. no D-Bus session over SSH)
+    pub fn is_available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+        *AVAILABLE.get_or_init(|| {
+            isolated(|| {
+                keyring::Entry::new(SERVICE, "probe").is_ok_and(|entry| {
+                    matches!(entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry))
+                })
+            })
+        })
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        isolated(|| match keyring::Entry::new(SERVICE, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(eyre!("Unable to read {key} from the keyring: {e}")),
+        })
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        isolated(|| {
+            keyring::Entry::new(SERVICE, key)?
+                .set_password(value)
+                .map_err(|e| eyre!("Unable to store {key} in the keyring: {e}"))
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        isolated(
+            || match keyring::Entry::new(SERVICE, key)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(eyre!("Unable to delete {key} from the keyring: {e}")),
+            },
+        )
+    }
+}
+
+/// AES-256-GCM encrypted file, for systems without a keyring
+///
+/// The key is generated on first use and readable by the current user only: this keeps
+/// secrets out of dotfiles and backups of the config, not away from the local user
+pub struct EncryptedFileStore {
+    dir: PathBuf,
+}
+
+impl EncryptedFileStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm> {
+        let path = self.dir.join("secrets.key");
+
+        let key = match std::fs::read_to_string(&path) {
+            Ok(encoded) => STANDARD.decode(encoded.trim())?,
+            // a new key would make the existing secrets unreadable, then overwrite them
+            Err(e) if e.kind() == ErrorKind::NotFound && self.dir.join("secrets.enc").exists() => {
+                return Err(eyre!(
+                    "{} is missing, unable to decrypt the secrets",
+                    path.display()
+                ));
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let mut key = vec![0u8; 32];
+                rand::thread_rng().fill_bytes(&mut key);
+
+                std::fs::create_dir_all(&self.dir)?;
+                write_private(&path, STANDARD.encode(&key).as_bytes())?;
+
+                key
+            }
+            Err(e) => return Err(eyre!("Unable to read {}: {e}", path.display())),
+        };
+
+        Aes256Gcm::new_from_slice(&key).map_err(|_| eyre!("Invalid key in {}", path.display()))
+    }
+
+    fn read(&self) -> Result<HashMap<String, String>> {
+        let path = self.dir.join("secrets.enc");
+        let encoded = match std::fs::read_to_string(&path) {
+            Ok(encoded) => encoded,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(eyre!("Unable to read {}: {e}", path.display())),
+        };
+
+        let data = STANDARD.decode(encoded.trim())?;
+        if data.len() < 12 {
+            return Err(eyre!("Corrupted secrets file"));
+        }
+
+        let (nonce, ciphertext) = data.split_at(12);
+        let plaintext = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| eyre!("Unable to decrypt the secrets file"))?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn write(&self, secrets: &HashMap<String, String>) -> Result<()> {
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher()?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                serde_json::to_vec(secrets)?.as_slice(),
+            )
+            .map_err(|_| eyre!("Unable to encrypt the secrets file"))?;
+
+        let data = [nonce.as_slice(), ciphertext.as_slice()].concat();
+        write_private(
+            &self.dir.join("secrets.enc"),
+            STANDARD.encode(data).as_bytes(),
+        )
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.read()?.remove(key))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+        secrets.insert(key.to_string(), value.to_string());
+
+        self.write(&secrets)
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        let mut secrets = self.read()?;
+
+        if secrets.remove(key).is_some() {
+            self.write(&secrets)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write a file readable by the current user only
+fn write_private(path: &std::path::Path, contents: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(contents)?;
+
+    Ok(())
+}
+
+/// Keyring when reachable, encrypted file otherwise
+///
+/// `TPAWS_SECRET_STORE=keyring|file` skips the detection
+pub fn store() -> Result<Box<dyn SecretStore>> {
+    let file =
+        || -> Result<Box<dyn SecretStore>> { Ok(Box::new(EncryptedFileStore::new(data_dir()?))) };
+
+    match std::env::var("TPAWS_SECRET_STORE").ok().as_deref() {
+        Some("keyring") => Ok(Box::new(KeyringStore)),
+        Some("file") => file(),
+        Some(other) => Err(eyre!(
+            "Unknown secret store `{other}`, use `keyring` or `file`"
+        )),
+        None if KeyringStore::is_available() => Ok(Box::new(KeyringStore)),
+        None => file(),
+    }
+}
+
+impl Config {
+    /// Call `f` with the store key and the field of every secret, profiles included
+    fn each_secret(
+        &mut self,
+        mut f: impl FnMut(&str, &mut Option<String>) -> Result<()>,
+    ) -> Result<()> {
+        for secret in <Secret as strum::IntoEnumIterator>::iter() {
+            f(&secret.to_string(), secret.field(self))?;
+
+            for (name, profile) in self.profiles.iter_mut() {
+                if let Some(field) = secret.profile_field(profile) {
+                    f(&format!("profiles.{name}.{secret}"), field)?;
+                }
+            }
+        }
+
+        self.drop_empty_slack();
+
+        Ok(())
+    }
+
+    /// Whether the config file still holds secrets in plaintext
+    pub(crate) fn has_plaintext_secrets(&self) -> bool {
+        let mut found = false;
+
+        let _ = self.clone().each_secret(|_, field| {
+            found |= field.is_some();
+            Ok(())
+        });
+
+        found
+    }
+
+    /// Move the secrets from the config to the store
+    ///
+    /// Missing secrets are deleted only when they were loaded, see [`Config::secrets_loaded`]
+    pub(crate) fn save_secrets(&mut self, store: &dyn SecretStore) -> Result<()> {
+        let loaded = self.secrets_loaded;
+
+        self.each_secret(|key, field| {
+            match field.take() {
+                Some(value) if store.get(key)?.as_deref() != Some(value.as_str()) => {
+                    store.set(key, &value)?
+                }
+                Some(_) => {}
+                None if loaded => store.delete(key)?,
+                None => {}
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Delete the stored secrets of the profiles of `previous` missing from this config
+    pub(crate) fn delete_removed_profile_secrets(
+        &self,
+        previous: &Config,
+        store: &dyn SecretStore,
+    ) -> Result<()> {
+        let removed = previous
+            .profiles
+            .keys()
+            .filter(|name| !self.profiles.contains_key(*name));
+
+        for name in removed {
+            for secret in <Secret as strum::IntoEnumIterator>::iter() {
+                if secret.profile_field(&mut Profile::default()).is_some() {
+                    store.delete(&format!("profiles.{name}.{secret}"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill the secrets missing from the config file
+    pub(crate) fn load_secrets(&mut self, store: &dyn SecretStore) -> Result<()> {
+        self.each_secret(|key, field| {
+            if field.is_none() {
+                *field = store.get(key)?;
+            }
+
+            Ok(())
+        })?;
+
+        self.secrets_loaded = true;
+
+        Ok(())
+    }
+
+    /// `Secret::field` creates the slack section, don't leave an empty one behind
+    fn drop_empty_slack(&mut self) {
+        if self.slack.as_ref() == Some(&SlackConfig::default()) {
+            self.slack = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> EncryptedFileStore {
+        let dir = std::env::temp_dir().join(format!(
+            "tpaws-secrets-{}-{}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+
+        EncryptedFileStore::new(dir)
+    }
+
+    #[test]
+    fn encrypted_file_roundtrip() {
+        let store = store();
+
+        assert_eq!(store.get("groq_api_key").unwrap(), None);
+
+        store.set("groq_api_key", "gsk_123").unwrap();
+        store.set("tp_apikey", "tp").unwrap();
+        store.delete("tp_apikey").unwrap();
+
+        assert_eq!(
+            store.get("groq_api_key").unwrap().as_deref(),
+            Some("gsk_123")
+        );
+        assert_eq!(store.get("tp_apikey").unwrap(), None);
+
+        let raw = std::fs::read_to_string(store.dir.join("secrets.enc")).unwrap();
+        assert!(!raw.contains("gsk_123"));
+
+        std::fs::remove_file(store.dir.join("secrets.key")).unwrap();
+        assert!(store.get("groq_api_key").is_err());
+        assert!(store.set("tp_apikey", "tp").is_err());
+        assert!(!store.dir.join("secrets.key").exists());
+
+        std::fs::remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_plaintext() {
+        let store = store();
+        let mut config: Config = serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "groq_api_key": "gsk_123",
+            "slack": { "bot_token": "xoxb-1", "channel": "#dev" },
+            "profiles": { "acme": { "tp_apikey": "tp-acme" } },
+        }))
+        .unwrap();
+
+        assert!(config.has_plaintext_secrets());
+
+        config.save_secrets(&store).unwrap();
+
+        assert!(!config.has_plaintext_secrets());
+        assert_eq!(
+            store.get("profiles.acme.tp_apikey").unwrap().as_deref(),
+            Some("tp-acme")
+        );
+        assert_eq!(
+            config.slack.as_ref().unwrap().channel.as_deref(),
+            Some("#dev")
+        );
+
+        config.load_secrets(&store).unwrap();
+
+        assert_eq!(config.groq_api_key.as_deref(), Some("gsk_123"));
+        assert_eq!(config.slack.unwrap().bot_token.as_deref(), Some("xoxb-1"));
+        assert_eq!(
+            config.profiles["acme"].tp_apikey.as_deref(),
+            Some("tp-acme")
+        );
+
+        std::fs::remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn unloaded_secrets_are_kept() {
+        let store = store();
+        store.set("tp_apikey", "tp").unwrap();
+
+        let mut config: Config = serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+        }))
+        .unwrap();
+
+        config.clone().save_secrets(&store).unwrap();
+        assert_eq!(store.get("tp_apikey").unwrap().as_deref(), Some("tp"));
+
+        config.load_secrets(&store).unwrap();
+        config.tp_apikey = None;
+        config.save_secrets(&store).unwrap();
+        assert_eq!(store.get("tp_apikey").unwrap(), None);
+
+        std::fs::remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn removed_profile_secrets_are_deleted() {
+        let store = store();
+        store.set("profiles.acme.tp_apikey", "tp-acme").unwrap();
+        store.set("profiles.beta.tp_apikey", "tp-beta").unwrap();
+        store.set("profiles.beta.ai_api_key", "sk-beta").unwrap();
+
+        let previous: Config = serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "profiles": { "acme": {}, "beta": {} },
+        }))
+        .unwrap();
+        let mut config = previous.clone();
+        config.profiles.remove("beta");
+
+        config
+            .delete_removed_profile_secrets(&previous, &store)
+            .unwrap();
+
+        assert_eq!(
+            store.get("profiles.acme.tp_apikey").unwrap().as_deref(),
+            Some("tp-acme")
+        );
+        assert_eq!(store.get("profiles.beta.tp_apikey").unwrap(), None);
+        assert_eq!(store.get("profiles.beta.ai_api_key").unwrap(), None);
+
+        std::fs::remove_dir_all(&store.dir).unwrap();
+    }
+}
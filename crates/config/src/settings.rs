@@ -0,0 +1,332 @@
+use serde::Serialize;
+
+use crate::{Config, Profile, ProjectConfig};
+
+/// Settings resolved in layers:
+/// flag > env > project `tpaws.json` > selected profile > global config > default
+///
+/// The legacy env vars (`AWS_PROFILE`, `TARGET_PROCESS_API_BASE_URL`) sit below the
+/// selected profile, so switching profile isn't undone by a leftover export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Setting {
+    /// TargetProcess project name
+    Project,
+
+    /// Target branch of new PRs
+    BaseBranch,
+    AwsProfile,
+
+    /// One of `groq`, `openai` or `anthropic`
+    AiProvider,
+    AiModel,
+    AiBaseUrl,
+
+    /// Git remote used to find the repository and push releases
+    Remote,
+    SlackChannel,
+
+    /// TargetProcess instance, e.g. `https://my-company.tpondemand.com`
+    TpUrl,
+}
+
+/// Layer a setting value comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Origin {
+    Flag,
+    Env(String),
+    Project,
+    Profile(String),
+    Global,
+    Default,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Flag => write!(f, "flag"),
+            Origin::Env(name) => write!(f, "env ${name}"),
+            Origin::Project => write!(f, "project tpaws.json"),
+            Origin::Profile(name) => write!(f, "profile {name}"),
+            Origin::Global => write!(f, "global config"),
+            Origin::Default => write!(f, "default"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Resolved {
+    pub value: String,
+    pub origin: Origin,
+}
+
+impl Setting {
+    /// Environment variable of the setting, e.g. `TPAWS_BASE_BRANCH`
+    pub fn env_name(&self) -> String {
+        format!("TPAWS_{}", self.to_string().to_uppercase())
+    }
+
+    /// Environment variable shared with other tools, below the selected profile
+    pub fn legacy_env_name(&self) -> Option<&'static str> {
+        match self {
+            Setting::AwsProfile => Some("AWS_PROFILE"),
+            Setting::TpUrl => Some("TARGET_PROCESS_API_BASE_URL"),
+            _ => None,
+        }
+    }
+
+    /// Provider dependent defaults (`ai_model`) are left to the caller
+    pub fn default_value(&self) -> Option<&'static str> {
+        match self {
+            Setting::BaseBranch => Some("develop"),
+            Setting::AwsProfile => Some("default"),
+            Setting::AiProvider => Some("groq"),
+            Setting::Remote => Some("origin"),
+            Setting::Project
+            | Setting::AiModel
+            | Setting::AiBaseUrl
+            | Setting::SlackChannel
+            | Setting::TpUrl => None,
+        }
+    }
+
+    fn project_value(&self, project: &ProjectConfig) -> Option<String> {
+        match self {
+            Setting::Project => project.name.clone(),
+            Setting::BaseBranch => project.base_branch.clone(),
+            Setting::AwsProfile => project.aws_profile.clone(),
+            Setting::AiModel => project.ai_model.clone(),
+            Setting::Remote => project.remote.clone(),
+            Setting::SlackChannel => project.slack.as_ref().and_then(|s| s.channel.clone()),
+            // personal, they come with an api key
+            Setting::AiProvider | Setting::AiBaseUrl | Setting::TpUrl => None,
+        }
+    }
+
+    fn profile_value(&self, profile: &Profile) -> Option<String> {
+        match self {
+            Setting::Project => profile.project.clone(),
+            Setting::BaseBranch => profile.base_branch.clone(),
+            Setting::AwsProfile => profile.aws_profile.clone(),
+            Setting::AiProvider => profile.ai_provider.clone(),
+            Setting::AiModel => profile.ai_model.clone(),
+            Setting::AiBaseUrl => profile.ai_base_url.clone(),
+            Setting::TpUrl => profile.tp_url.clone(),
+            // the remote selects the profile, slack is shared
+            Setting::Remote | Setting::SlackChannel => None,
+        }
+    }
+
+    fn global_value(&self, config: &Config) -> Option<String> {
+        match self {
+            Setting::Project => config.project.clone(),
+            Setting::BaseBranch => config.base_branch.clone(),
+            Setting::AwsProfile => config.aws_profile.clone(),
+            Setting::AiProvider => config.ai_provider.clone(),
+            Setting::AiModel => config.ai_model.clone(),
+            Setting::AiBaseUrl => config.ai_base_url.clone(),
+            Setting::Remote => config.remote.clone(),
+            Setting::SlackChannel => config.slack.as_ref().and_then(|s| s.channel.clone()),
+            Setting::TpUrl => config.tp_url.clone(),
+        }
+    }
+
+    pub fn resolve(
+        &self,
+        flag: Option<String>,
+        project: Option<&ProjectConfig>,
+        config: &Config,
+    ) -> Option<Resolved> {
+        self.resolve_with(flag, |name| std::env::var(name).ok(), project, config)
+    }
+
+    /// Same as [`Setting::resolve`] with a custom environment lookup
+    pub fn resolve_with(
+        &self,
+        flag: Option<String>,
+        env: impl Fn(&str) -> Option<String>,
+        project: Option<&ProjectConfig>,
+        config: &Config,
+    ) -> Option<Resolved> {
+        let name = self.env_name();
+        let mut layers = vec![(flag, Origin::Flag), (env(&name), Origin::Env(name))];
+
+        let legacy = self
+            .legacy_env_name()
+            .map(|name| (env(name), Origin::Env(name.into())));
+
+        match config.profile() {
+            Some((name, profile)) => {
+                layers.push((project.and_then(|p| self.project_value(p)), Origin::Project));
+                layers.push((self.profile_value(profile), Origin::Profile(name.into())));
+                layers.extend(legacy);
+            }
+            None => {
+                layers.extend(legacy);
+                layers.push((project.and_then(|p| self.project_value(p)), Origin::Project));
+            }
+        }
+
+        layers.push((self.global_value(config), Origin::Global));
+        layers.push((self.default_value().map(String::from), Origin::Default));
+
+        layers.into_iter().find_map(|(value, origin)| {
+            value
+                .filter(|v| !v.is_empty())
+                .map(|value| Resolved { value, origin })
+        })
+    }
+
+    /// Resolved value without its origin
+    pub fn value(
+        &self,
+        flag: Option<String>,
+        project: Option<&ProjectConfig>,
+        config: &Config,
+    ) -> Option<String> {
+        self.resolve(flag, project, config).map(|r| r.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "username": "john.doe",
+            "pr_name": "John Doe",
+            "pr_email": "john@doe.com",
+            "user_id": 1,
+            "base_branch": "main",
+            "slack": { "channel": "#global" },
+        }))
+        .unwrap()
+    }
+
+    fn project() -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": "Web",
+            "base_branch": "next",
+            "slack": { "channel": "#web" },
+        }))
+        .unwrap()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn layers_order() {
+        let config = config();
+        let project = project();
+        let env = |name: &str| (name == "TPAWS_BASE_BRANCH").then(|| "env".to_string());
+
+        let base = |flag: Option<&str>, env: &dyn Fn(&str) -> Option<String>, project| {
+            Setting::BaseBranch
+                .resolve_with(flag.map(String::from), env, project, &config)
+                .unwrap()
+        };
+
+        assert_eq!(
+            base(Some("flag"), &env, Some(&project)).origin,
+            Origin::Flag
+        );
+        assert_eq!(
+            base(None, &env, Some(&project)).origin,
+            Origin::Env("TPAWS_BASE_BRANCH".into())
+        );
+        assert_eq!(base(None, &no_env, Some(&project)).value, "next");
+        assert_eq!(base(None, &no_env, None).value, "main");
+        assert_eq!(
+            Setting::Remote.resolve_with(None, no_env, None, &config),
+            Some(Resolved {
+                value: "origin".into(),
+                origin: Origin::Default
+            })
+        );
+        assert_eq!(
+            Setting::SlackChannel
+                .resolve_with(None, no_env, Some(&project), &config)
+                .unwrap()
+                .value,
+            "#web"
+        );
+        assert_eq!(
+            Setting::Project.resolve_with(None, no_env, None, &config),
+            None
+        );
+    }
+
+    #[test]
+    fn profile_layer() {
+        let mut config = config();
+        config.profiles.insert(
+            "acme".into(),
+            Profile {
+                base_branch: Some("trunk".into()),
+                ..Default::default()
+            },
+        );
+        config.active_profile = Some("acme".into());
+
+        assert_eq!(
+            Setting::BaseBranch.resolve_with(None, no_env, None, &config),
+            Some(Resolved {
+                value: "trunk".into(),
+                origin: Origin::Profile("acme".into())
+            })
+        );
+        assert_eq!(
+            Setting::BaseBranch
+                .resolve_with(None, no_env, Some(&project()), &config)
+                .unwrap()
+                .value,
+            "next"
+        );
+    }
+
+    #[test]
+    fn aws_profile_env_fallback() {
+        let env = |name: &str| (name == "AWS_PROFILE").then(|| "work".to_string());
+        let resolved = Setting::AwsProfile
+            .resolve_with(None, env, None, &config())
+            .unwrap();
+
+        assert_eq!(resolved.value, "work");
+        assert_eq!(resolved.origin, Origin::Env("AWS_PROFILE".into()));
+    }
+
+    #[test]
+    fn profile_over_legacy_env() {
+        let mut config = config();
+        config.profiles.insert(
+            "acme".into(),
+            Profile {
+                aws_profile: Some("acme".into()),
+                ..Default::default()
+            },
+        );
+        config.active_profile = Some("acme".into());
+
+        let env = |name: &str| (name == "AWS_PROFILE").then(|| "work".to_string());
+        assert_eq!(
+            Setting::AwsProfile.resolve_with(None, env, None, &config),
+            Some(Resolved {
+                value: "acme".into(),
+                origin: Origin::Profile("acme".into())
+            })
+        );
+
+        let env = |name: &str| (name == "TPAWS_AWS_PROFILE").then(|| "work".to_string());
+        assert_eq!(
+            Setting::AwsProfile
+                .resolve_with(None, env, None, &config)
+                .unwrap()
+                .value,
+            "work"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reviewer {
+    pub name: String,
+
+    /// Slack member ID, e.g. `U067RQQ94NM`
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// Incoming webhook used to post PR notifications
+    pub webhook_url: Option<String>,
+
+    /// Bot token (`xoxb-...`), enables threaded PR updates; requires `channel`
+    pub bot_token: Option<String>,
+    pub channel: Option<String>,
+
+    /// Where release changelogs are announced, defaults to `channel`
+    pub release_channel: Option<String>,
+
+    /// Your own Slack member ID, used to mention the PR author
+    pub user_id: Option<String>,
+
+    #[serde(default)]
+    pub reviewers: Vec<Reviewer>,
+}
+
+impl SlackConfig {
+    /// Project settings win over the global ones, reviewers are combined.
+    /// The webhook and the bot token only come from the global config
+    pub fn merge(global: Option<&SlackConfig>, project: Option<&SlackConfig>) -> SlackConfig {
+        let global = global.cloned().unwrap_or_default();
+        let Some(project) = project.cloned() else {
+            return global;
+        };
+
+        let mut reviewers = project.reviewers;
+        for reviewer in global.reviewers {
+            if !reviewers.iter().any(|r| r.id == reviewer.id) {
+                reviewers.push(reviewer);
+            }
+        }
+
+        SlackConfig {
+            webhook_url: global.webhook_url,
+            bot_token: global.bot_token,
+            channel: project.channel.or(global.channel),
+            release_channel: project.release_channel.or(global.release_channel),
+            user_id: project.user_id.or(global.user_id),
+            reviewers,
+        }
+    }
+
+    /// Drop the webhook and the bot token, returns whether there were any
+    pub fn remove_secrets(&mut self) -> bool {
+        let webhook_url = self.webhook_url.take();
+        let bot_token = self.bot_token.take();
+
+        webhook_url.is_some() || bot_token.is_some()
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.is_bot() || self.webhook_url.as_ref().is_some_and(|u| !u.is_empty())
+    }
+
+    /// Post through the Web API instead of the webhook
+    pub fn is_bot(&self) -> bool {
+        self.bot_token.as_ref().is_some_and(|t| !t.is_empty()) && self.channel.is_some()
+    }
+
+    /// Add or rename a reviewer, matching by Slack ID
+    pub fn add_reviewer(&mut self, reviewer: Reviewer) {
+        match self.reviewers.iter_mut().find(|r| r.id == reviewer.id) {
+            Some(existing) => existing.name = reviewer.name,
+            None => self.reviewers.push(reviewer),
+        }
+    }
+
+    /// Remove a reviewer by name or Slack ID, returns whether one was removed
+    pub fn remove_reviewer(&mut self, name_or_id: &str) -> bool {
+        let before = self.reviewers.len();
+        self.reviewers
+            .retain(|r| r.id != name_or_id && !r.name.eq_ignore_ascii_case(name_or_id));
+
+        before != self.reviewers.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reviewer(name: &str, id: &str) -> Reviewer {
+        Reviewer {
+            name: name.into(),
+            id: id.into(),
+        }
+    }
+
+    #[test]
+    fn merge_project_over_global() {
+        let global = SlackConfig {
+            webhook_url: Some("https://hooks.slack.com/global".into()),
+            bot_token: None,
+            channel: Some("#dev".into()),
+            release_channel: None,
+            user_id: Some("U1".into()),
+            reviewers: vec![reviewer("Jane", "U2"), reviewer("John", "U3")],
+        };
+
+        let mut project = SlackConfig {
+            webhook_url: Some("https://hooks.slack.com/project".into()),
+            bot_token: Some("xoxb-project".into()),
+            channel: Some("#project".into()),
+            reviewers: vec![reviewer("Janet", "U2")],
+            ..Default::default()
+        };
+
+        let merged = SlackConfig::merge(Some(&global), Some(&project));
+
+        assert_eq!(
+            merged.webhook_url.as_deref(),
+            Some("https://hooks.slack.com/global")
+        );
+        assert_eq!(merged.bot_token, None);
+        assert_eq!(merged.channel.as_deref(), Some("#project"));
+        assert_eq!(merged.user_id.as_deref(), Some("U1"));
+        assert_eq!(
+            merged.reviewers,
+            vec![reviewer("Janet", "U2"), reviewer("John", "U3")]
+        );
+
+        assert!(project.remove_secrets());
+        assert!(!project.remove_secrets());
+        assert_eq!(project.channel.as_deref(), Some("#project"));
+    }
+
+    #[test]
+    fn add_and_remove_reviewers() {
+        let mut config = SlackConfig::default();
+
+        config.add_reviewer(reviewer("Jane", "U2"));
+        config.add_reviewer(reviewer("Jane Doe", "U2"));
+        assert_eq!(config.reviewers, vec![reviewer("Jane Doe", "U2")]);
+
+        assert!(config.remove_reviewer("jane doe"));
+        assert!(!config.remove_reviewer("U2"));
+        assert!(!config.is_configured());
+
+        config.bot_token = Some("xoxb-1".into());
+        assert!(!config.is_bot());
+
+        config.channel = Some("#dev".into());
+        assert!(config.is_bot());
+        assert!(config.is_configured());
+    }
+}
@@ -21,13 +21,14 @@ impl TryFrom<String> for Shell {
     }
 }
 
-impl ToString for Shell {
-    fn to_string(&self) -> String {
-        match self {
+impl std::fmt::Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rcfile = match self {
             Self::Bash => ".bashrc",
             Self::Zsh => ".zshrc",
-        }
-        .into()
+        };
+
+        write!(f, "{rcfile}")
     }
 }
 
@@ -40,6 +41,7 @@ pub fn get_user_id() -> Option<String> {
 
 // we don't return error if the write operation fails
 // instead show a warning message to the user
+// rc files are plaintext: never use it for secrets, see `crate::secrets`
 pub async fn inject_env(key: &str, value: &str) -> Result<()> {
     std::env::set_var(key, value);
 
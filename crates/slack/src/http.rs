@@ -10,10 +10,26 @@ pub(crate) fn make_client() -> Result<reqwest::Client> {
     Ok(client)
 }
 
+/// Client for the Web API, authenticated with a bot token (`xoxb-...`)
+pub(crate) fn make_bot_client(token: &str) -> Result<reqwest::Client> {
+    let mut headers = get_headers();
+    headers.insert(AUTHORIZATION, format!("Bearer {token}").parse()?);
+
+    let client = reqwest::ClientBuilder::new()
+        .https_only(true)
+        .default_headers(headers)
+        .build()?;
+
+    Ok(client)
+}
+
 pub(crate) fn get_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(ACCEPT, "application/json".parse().unwrap());
-    headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    headers.insert(
+        CONTENT_TYPE,
+        "application/json; charset=utf-8".parse().unwrap(),
+    );
 
     headers
 }
@@ -1,54 +1,149 @@
+//! Subset of Slack Block Kit, see https://api.slack.com/reference/block-kit
+
+// `derive_setters` expands to a struct update even for single-field structs
+#![allow(clippy::needless_update)]
+
 use derive_setters::Setters;
 use serde::Serialize;
 
+/// Slack rejects section texts longer than this
+pub const MAX_TEXT_LENGTH: usize = 3000;
+
+/// Slack rejects header texts longer than this
+pub const MAX_HEADER_LENGTH: usize = 150;
+
 #[derive(serde::Serialize, Default, Setters, Debug, Clone)]
 pub struct Message {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<String>,
+
+    /// Notification fallback, required by the Web API when sending blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+
+    /// Message to edit with `chat.update`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<String>,
+
+    /// Parent message when replying in a thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_ts: Option<String>,
+
     blocks: Vec<Block>,
 }
 
-#[allow(dead_code)]
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockType {
+    // blocks
     Actions,
-    Text,
-    Section,
+    Context,
     Divider,
+    Header,
+    Image,
+    #[default]
+    Section,
+
+    // text objects
     Mrkdwn,
     PlainText,
+
+    // elements
     Button,
 }
 
-impl Default for BlockType {
-    fn default() -> Self {
-        Self::Text
-    }
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonStyle {
+    Primary,
+    Danger,
 }
 
 #[derive(serde::Serialize, Default, Setters, Debug, Clone)]
+#[setters(strip_option)]
 pub struct Block {
     #[serde(rename = "type")]
     _type: BlockType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     text: Option<TextBlock>,
-    elements: Option<Vec<Button>>,
+
+    /// Section fields, rendered in two columns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<TextBlock>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elements: Option<Vec<Element>>,
+
+    /// Element shown on the right side of a section
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessory: Option<Element>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alt_text: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<TextBlock>,
 }
 
 impl Block {
+    /// `t` is the text type, either `Mrkdwn` or `PlainText`
     pub fn section(t: BlockType, text: &str) -> Self {
+        let text = match t {
+            BlockType::PlainText => TextBlock::plain(text),
+            _ => TextBlock::mrkdwn(text),
+        };
+
         Self {
-            _type: BlockType::Text,
-            text: Some(TextBlock {
-                _type: t,
-                text: text.into(),
-                ..Default::default()
-            }),
+            _type: BlockType::Section,
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+
+    /// Section made of up to 10 two-column fields
+    pub fn section_fields(fields: Vec<TextBlock>) -> Self {
+        Self {
+            _type: BlockType::Section,
+            fields: Some(fields),
+            ..Default::default()
+        }
+    }
+
+    pub fn header(text: &str) -> Self {
+        Self {
+            _type: BlockType::Header,
+            text: Some(TextBlock::plain(&truncate(text, MAX_HEADER_LENGTH))),
+            ..Default::default()
+        }
+    }
+
+    /// Small, muted line of texts and images
+    pub fn context(elements: Vec<Element>) -> Self {
+        Self {
+            _type: BlockType::Context,
+            elements: Some(elements),
+            ..Default::default()
+        }
+    }
+
+    pub fn image(url: &str, alt_text: &str) -> Self {
+        Self {
+            _type: BlockType::Image,
+            image_url: Some(url.into()),
+            alt_text: Some(alt_text.into()),
             ..Default::default()
         }
     }
 
     pub fn divider() -> Self {
         Self {
-            text: None,
             _type: BlockType::Divider,
             ..Default::default()
         }
@@ -57,36 +152,86 @@ impl Block {
     pub fn actions(items: Vec<Button>) -> Self {
         Self {
             _type: BlockType::Actions,
-            elements: Some(items),
+            elements: Some(items.into_iter().map(Element::Button).collect()),
             ..Default::default()
         }
     }
 }
 
 #[derive(serde::Serialize, Default, Setters, Debug, Clone)]
-struct TextBlock {
+pub struct TextBlock {
     #[serde(rename = "type")]
     _type: BlockType,
     text: String,
-    emoji: bool,
+
+    /// Only allowed on `plain_text`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emoji: Option<bool>,
 }
 
 impl TextBlock {
     pub fn plain(content: &str) -> Self {
         Self {
             _type: BlockType::PlainText,
-            text: content.into(),
-            ..Default::default()
+            text: truncate(content, MAX_TEXT_LENGTH),
+            emoji: Some(true),
+        }
+    }
+
+    pub fn mrkdwn(content: &str) -> Self {
+        Self {
+            _type: BlockType::Mrkdwn,
+            text: truncate(content, MAX_TEXT_LENGTH),
+            emoji: None,
+        }
+    }
+}
+
+/// Elements allowed in `actions`, `context` and section accessories
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Element {
+    Button(Button),
+    Text(TextBlock),
+    Image(ImageElement),
+}
+
+#[derive(serde::Serialize, Setters, Debug, Clone)]
+pub struct ImageElement {
+    #[serde(rename = "type")]
+    _type: BlockType,
+    image_url: String,
+    alt_text: String,
+}
+
+impl ImageElement {
+    pub fn new(url: &str, alt_text: &str) -> Self {
+        Self {
+            _type: BlockType::Image,
+            image_url: url.into(),
+            alt_text: alt_text.into(),
         }
     }
 }
 
 #[derive(serde::Serialize, Setters, Default, Debug, Clone)]
+#[setters(strip_option)]
 pub struct Button {
     #[serde(rename = "type")]
     _type: BlockType,
-    url: Option<String>,
     text: TextBlock,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<ButtonStyle>,
 }
 
 impl Button {
@@ -95,6 +240,164 @@ impl Button {
             _type: BlockType::Button,
             url: Some(href.into()),
             text: TextBlock::plain(label),
+            ..Default::default()
         }
     }
 }
+
+/// Escape the characters Slack reads as mrkdwn control characters
+///
+/// For user content only, links like `<url|label>` must stay unescaped
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Cut `text` to `max` characters, ending with an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+
+    let mut truncated = text.chars().take(max - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn escape_control_characters() {
+        assert_eq!(
+            escape("Fix <b> & <!here>"),
+            "Fix &lt;b&gt; &amp; &lt;!here&gt;"
+        );
+    }
+
+    #[test]
+    fn section_is_serialized_as_section() {
+        let block = Block::section(BlockType::Mrkdwn, "*hello*");
+
+        assert_eq!(
+            serde_json::to_value(block).unwrap(),
+            json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": "*hello*" }
+            })
+        );
+    }
+
+    #[test]
+    fn section_with_fields_and_accessory() {
+        let block = Block::section_fields(vec![
+            TextBlock::mrkdwn("*Version*\n1.2.0"),
+            TextBlock::plain("Today"),
+        ])
+        .accessory(Element::Image(ImageElement::new(
+            "https://example.com/logo.png",
+            "logo",
+        )));
+
+        assert_eq!(
+            serde_json::to_value(block).unwrap(),
+            json!({
+                "type": "section",
+                "fields": [
+                    { "type": "mrkdwn", "text": "*Version*\n1.2.0" },
+                    { "type": "plain_text", "text": "Today", "emoji": true }
+                ],
+                "accessory": {
+                    "type": "image",
+                    "image_url": "https://example.com/logo.png",
+                    "alt_text": "logo"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn header_context_image_and_divider() {
+        let blocks = vec![
+            Block::header("Release 1.2.0"),
+            Block::context(vec![
+                Element::Image(ImageElement::new("https://example.com/a.png", "author")),
+                Element::Text(TextBlock::mrkdwn("by <@U1>")),
+            ]),
+            Block::image("https://example.com/chart.png", "chart")
+                .title(TextBlock::plain("Burndown")),
+            Block::divider(),
+        ];
+
+        assert_eq!(
+            serde_json::to_value(blocks).unwrap(),
+            json!([
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": "Release 1.2.0", "emoji": true }
+                },
+                {
+                    "type": "context",
+                    "elements": [
+                        { "type": "image", "image_url": "https://example.com/a.png", "alt_text": "author" },
+                        { "type": "mrkdwn", "text": "by <@U1>" }
+                    ]
+                },
+                {
+                    "type": "image",
+                    "image_url": "https://example.com/chart.png",
+                    "alt_text": "chart",
+                    "title": { "type": "plain_text", "text": "Burndown", "emoji": true }
+                },
+                { "type": "divider" }
+            ])
+        );
+    }
+
+    #[test]
+    fn actions_with_styled_buttons() {
+        let message = Message::default()
+            .text(Some("PR".into()))
+            .blocks(vec![Block::actions(vec![
+                Button::link("https://example.com/pr", "Code Commit").style(ButtonStyle::Primary),
+                Button::link("https://example.com/tp", "Target Process"),
+            ])]);
+
+        assert_eq!(
+            serde_json::to_value(message).unwrap(),
+            json!({
+                "text": "PR",
+                "blocks": [{
+                    "type": "actions",
+                    "elements": [
+                        {
+                            "type": "button",
+                            "text": { "type": "plain_text", "text": "Code Commit", "emoji": true },
+                            "url": "https://example.com/pr",
+                            "style": "primary"
+                        },
+                        {
+                            "type": "button",
+                            "text": { "type": "plain_text", "text": "Target Process", "emoji": true },
+                            "url": "https://example.com/tp"
+                        }
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn long_texts_are_truncated() {
+        let header = Block::header(&"a".repeat(200));
+        let value = serde_json::to_value(header).unwrap();
+        let text = value["text"]["text"].as_str().unwrap();
+
+        assert_eq!(text.chars().count(), MAX_HEADER_LENGTH);
+        assert!(text.ends_with('…'));
+    }
+}
@@ -0,0 +1,86 @@
+use color_eyre::{eyre::eyre, Result};
+use serde::Deserialize;
+
+use crate::{http, models::Message};
+
+const API_URL: &str = "https://slack.com/api";
+
+/// Slack Web API client, used in bot-token mode
+#[derive(Debug, Clone)]
+pub struct Client {
+    client: reqwest::Client,
+}
+
+/// Reference to a posted message, needed to edit it or reply in its thread
+#[derive(Debug, Clone)]
+pub struct PostedMessage {
+    pub channel: String,
+    pub ts: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    error: Option<String>,
+    channel: Option<String>,
+    ts: Option<String>,
+}
+
+impl Client {
+    pub fn new(token: &str) -> Result<Self> {
+        Ok(Self {
+            client: http::make_bot_client(token)?,
+        })
+    }
+
+    async fn call(&self, method: &str, message: &Message) -> Result<PostedMessage> {
+        let response = self
+            .client
+            .post(format!("{API_URL}/{method}"))
+            .json(message)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ApiResponse>()
+            .await?;
+
+        if !response.ok {
+            return Err(eyre!(
+                "slack {method} failed: {}",
+                response.error.unwrap_or("unknown error".into())
+            ));
+        }
+
+        match (response.channel, response.ts) {
+            (Some(channel), Some(ts)) => Ok(PostedMessage { channel, ts }),
+            _ => Err(eyre!("slack {method} returned no message reference")),
+        }
+    }
+
+    /// `chat.postMessage`, the message must have a channel
+    pub async fn post_message(&self, message: &Message) -> Result<PostedMessage> {
+        self.call("chat.postMessage", message).await
+    }
+
+    /// Reply in the thread of `parent`
+    pub async fn reply(&self, parent: &PostedMessage, message: Message) -> Result<PostedMessage> {
+        let message = message
+            .channel(Some(parent.channel.clone()))
+            .thread_ts(Some(parent.ts.clone()));
+
+        self.post_message(&message).await
+    }
+
+    /// `chat.update`, replacing the content of `target`
+    pub async fn update_message(
+        &self,
+        target: &PostedMessage,
+        message: Message,
+    ) -> Result<PostedMessage> {
+        let message = message
+            .channel(Some(target.channel.clone()))
+            .ts(Some(target.ts.clone()));
+
+        self.call("chat.update", &message).await
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use super::{Entry, ReleaseLog};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ChangelogFormat {
+    /// `## <version>` sections with links to TargetProcess
+    Markdown,
+
+    /// Same as markdown, without links
+    Plain,
+
+    /// https://keepachangelog.com
+    KeepAChangelog,
+    Json,
+    Html,
+
+    /// Slack `mrkdwn`
+    Slack,
+}
+
+impl ChangelogFormat {
+    /// Formats whose output can be merged into an existing file, they need `## <version>`
+    /// headings to find the versions already present, so not without the titles
+    pub fn supports_prepend(&self, no_title: bool) -> bool {
+        !no_title && matches!(self, Self::Markdown | Self::KeepAChangelog)
+    }
+}
+
+const FEATURES: &str = "Features";
+const BUG_FIXES: &str = "Bug fixes";
+
+fn entity_link(base_url: &str, id: usize) -> String {
+    format!("{base_url}/entity/{id}")
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Slack mrkdwn only gives a meaning to these three
+fn mrkdwn_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `(title, entries)` pairs, skipping empty groups
+fn groups(release: &ReleaseLog) -> Vec<(&'static str, Vec<&Entry>)> {
+    [
+        (FEATURES, release.features().collect::<Vec<_>>()),
+        (BUG_FIXES, release.bug_fixes().collect()),
+    ]
+    .into_iter()
+    .filter(|(_, entries)| !entries.is_empty())
+    .collect()
+}
+
+/// `base_url` is the TargetProcess instance the entries link to
+pub fn render(
+    releases: &[ReleaseLog],
+    format: ChangelogFormat,
+    no_title: bool,
+    base_url: &str,
+) -> String {
+    if format == ChangelogFormat::Json {
+        return serde_json::to_string_pretty(releases).unwrap_or_default();
+    }
+
+    let mut lines: Vec<String> = vec![];
+
+    for release in releases {
+        if !no_title {
+            lines.push(match format {
+                ChangelogFormat::Markdown => format!("## {}", release.version),
+                ChangelogFormat::KeepAChangelog => format!("## [{}]", release.version),
+                ChangelogFormat::Html => format!("<h2>{}</h2>", html_escape(&release.version)),
+                ChangelogFormat::Slack => format!("*{}*", release.version),
+                _ => release.version.clone(),
+            });
+        }
+
+        for (title, entries) in groups(release) {
+            lines.push(match format {
+                ChangelogFormat::Markdown => format!("### {title}"),
+                ChangelogFormat::KeepAChangelog if title == BUG_FIXES => "### Fixed".into(),
+                ChangelogFormat::KeepAChangelog => "### Added".into(),
+                ChangelogFormat::Html => format!("<h3>{title}</h3>\n<ul>"),
+                ChangelogFormat::Slack => format!("_{title}_"),
+                _ => format!("{title}:"),
+            });
+
+            for Entry { id, name, .. } in entries {
+                lines.push(match format {
+                    ChangelogFormat::Plain => format!("- [{id}] {name}"),
+                    ChangelogFormat::Html => format!(
+                        "<li><a href=\"{}\">#{id}</a> {}</li>",
+                        entity_link(base_url, *id),
+                        html_escape(name)
+                    ),
+                    ChangelogFormat::Slack => {
+                        format!(
+                            "• <{}|#{id}> {}",
+                            entity_link(base_url, *id),
+                            mrkdwn_escape(name)
+                        )
+                    }
+                    _ => format!("- [{id}]({}) {name}", entity_link(base_url, *id)),
+                });
+            }
+
+            if format == ChangelogFormat::Html {
+                lines.push("</ul>".into());
+            }
+        }
+
+        if format != ChangelogFormat::Plain {
+            lines.push(String::new());
+        }
+    }
+
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Versions that already have a `## <version>` / `## [<version>]` section
+pub fn existing_versions(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("## "))
+        .filter_map(|title| {
+            title
+                .trim()
+                .trim_start_matches('[')
+                .split([']', ' '])
+                .next()
+                .map(|v| v.to_string())
+        })
+        .collect()
+}
+
+/// Inserts `section` above the first `## ` section of `content`,
+/// keeping the file header (e.g. `# Changelog`) on top
+pub fn prepend(content: &str, section: &str) -> String {
+    let section = section.trim();
+
+    if section.is_empty() {
+        return content.to_string();
+    }
+
+    let position = content
+        .match_indices("## ")
+        .find(|(i, _)| *i == 0 || content[..*i].ends_with('\n'))
+        .map(|(i, _)| i);
+
+    match position {
+        Some(i) => format!("{}{section}\n\n{}", &content[..i], &content[i..]),
+        None if content.trim().is_empty() => format!("{section}\n"),
+        None => format!("{}\n\n{section}\n", content.trim_end()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn release(version: &str) -> ReleaseLog {
+        ReleaseLog {
+            version: version.into(),
+            entries: vec![
+                Entry {
+                    id: 1,
+                    name: "Add login".into(),
+                    entity_type: "UserStory".into(),
+                },
+                Entry {
+                    id: 2,
+                    name: "Fix <crash>".into(),
+                    entity_type: "Bug".into(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn render_plain() {
+        let output = render(
+            &[release("1.4.0")],
+            ChangelogFormat::Plain,
+            false,
+            "https://tp",
+        );
+
+        assert_eq!(
+            output,
+            "1.4.0\nFeatures:\n- [1] Add login\nBug fixes:\n- [2] Fix <crash>"
+        );
+    }
+
+    #[test]
+    fn render_slack_escapes_names() {
+        let output = render(
+            &[release("1.4.0")],
+            ChangelogFormat::Slack,
+            true,
+            "https://tp",
+        );
+
+        assert!(output.ends_with("|#2> Fix &lt;crash&gt;"));
+    }
+
+    #[test]
+    fn render_json() {
+        let releases = vec![release("1.4.0")];
+        let output = render(&releases, ChangelogFormat::Json, false, "https://tp");
+
+        let parsed: Vec<ReleaseLog> = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed, releases);
+    }
+
+    #[test]
+    fn find_existing_versions() {
+        let content = "# Changelog\n\n## [1.4.0]\n### Added\n\n## 1.3.0\n- foo\n";
+        let versions = existing_versions(content);
+
+        assert!(versions.contains("1.4.0"));
+        assert!(versions.contains("1.3.0"));
+        assert_eq!(versions.len(), 2);
+    }
+
+    #[test]
+    fn prepend_needs_version_headings() {
+        assert!(ChangelogFormat::Markdown.supports_prepend(false));
+        assert!(ChangelogFormat::KeepAChangelog.supports_prepend(false));
+        assert!(!ChangelogFormat::Plain.supports_prepend(false));
+
+        // without the headings every run would prepend the same versions again
+        assert!(!ChangelogFormat::Markdown.supports_prepend(true));
+        assert!(!ChangelogFormat::KeepAChangelog.supports_prepend(true));
+
+        let section = render(
+            &[release("1.4.0")],
+            ChangelogFormat::Markdown,
+            true,
+            "https://tp",
+        );
+        assert!(existing_versions(&section).is_empty());
+    }
+
+    #[test]
+    fn prepend_section() {
+        let content = "# Changelog\n\nAll notable changes.\n\n## [1.3.0]\n- foo\n";
+        let output = prepend(content, "## [1.4.0]\n- bar");
+
+        assert_eq!(
+            output,
+            "# Changelog\n\nAll notable changes.\n\n## [1.4.0]\n- bar\n\n## [1.3.0]\n- foo\n"
+        );
+
+        assert_eq!(prepend("", "## 1.0.0"), "## 1.0.0\n");
+        assert_eq!(
+            prepend("# Changelog\n", "## 1.0.0"),
+            "# Changelog\n\n## 1.0.0\n"
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use std::{collections::HashMap, sync::Arc};
+
+use cached::proc_macro::cached;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tokio::task::{JoinError, JoinSet};
+
+use crate::{
+    errors::ApiError,
+    get_releases,
+    models::v2::assignable::{EntityType, IdAndName},
+    release_name, Param, ResponseListV2, Result, TargetProcessClient,
+};
+
+mod format;
+
+pub use format::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    pub id: usize,
+    pub name: String,
+    pub entity_type: String,
+}
+
+impl Entry {
+    pub fn is_bug(&self) -> bool {
+        self.entity_type.to_lowercase() == "bug"
+    }
+}
+
+/// Tickets shipped with a version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReleaseLog {
+    pub version: String,
+    pub entries: Vec<Entry>,
+}
+
+impl ReleaseLog {
+    pub fn features(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|e| !e.is_bug())
+    }
+
+    pub fn bug_fixes(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|e| e.is_bug())
+    }
+}
+
+/// Extracts the version from a release name or git tag (`<prefix>@1.2.3`, `v1.2.3`, `1.2.3`),
+/// without prefix only the bare versions match, like the names from [`crate::release_name`]
+pub fn parse_release_name(name: &str, prefix: &str) -> Option<Version> {
+    let raw = match prefix.is_empty() {
+        true if name.contains('@') => return None,
+        true => name,
+        false => name.strip_prefix(&format!("{prefix}@"))?,
+    };
+
+    Version::parse(raw.trim().trim_start_matches('v')).ok()
+}
+
+/// Parses a range bound, partial versions are expanded to cover the whole range
+/// (`1.4` is `1.4.0` as lower bound and `1.4.<max>` as upper bound)
+pub fn parse_version_bound(value: &str, upper: bool) -> Option<Version> {
+    let value = value.trim().trim_start_matches('v');
+
+    if let Ok(version) = Version::parse(value) {
+        return Some(version);
+    }
+
+    let parts: Vec<u64> = value
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let fill = if upper { u64::MAX } else { 0 };
+
+    match parts.as_slice() {
+        [major] => Some(Version::new(*major, fill, fill)),
+        [major, minor] => Some(Version::new(*major, *minor, fill)),
+        _ => None,
+    }
+}
+
+/// Keeps the versions within `from..=to`, sorted from the newest and without duplicates
+pub fn filter_versions(
+    versions: impl IntoIterator<Item = Version>,
+    from: Option<&Version>,
+    to: Option<&Version>,
+) -> Vec<Version> {
+    let mut versions: Vec<Version> = versions
+        .into_iter()
+        .filter(|v| from.is_none_or(|from| v >= from))
+        .filter(|v| to.is_none_or(|to| v <= to))
+        .collect();
+
+    versions.sort_by(|a, b| b.cmp(a));
+    versions.dedup();
+
+    versions
+}
+
+/// Versions of the TargetProcess releases of a project matching the prefix
+pub async fn get_release_versions(
+    client: &TargetProcessClient,
+    project_name: &str,
+    release_prefix: &str,
+) -> Result<Vec<Version>> {
+    let releases = get_releases(client, project_name, release_prefix).await?;
+
+    Ok(releases
+        .iter()
+        .filter_map(|r| parse_release_name(&r.name, release_prefix))
+        .collect())
+}
+
+/// Number of releases requested with a single `Release.Name in [...]` query
+const RELEASES_PER_QUERY: usize = 20;
+
+/// Maximum number of queries running at the same time
+const MAX_CONCURRENT_QUERIES: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct ReleaseFailure {
+    pub version: String,
+    pub error: ApiError,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Changelog {
+    /// Non empty releases, newest first
+    pub releases: Vec<ReleaseLog>,
+    pub failures: Vec<ReleaseFailure>,
+}
+
+#[cached(
+    key = "(String, String, String, Vec<Version>)",
+    convert = r#"{ (client.base_url().to_string(), project_name.clone(), release_prefix.clone(), versions.clone()) }"#
+)]
+pub async fn generate_changelog(
+    client: &TargetProcessClient,
+    project_name: String,
+    release_prefix: String,
+    versions: Vec<Version>,
+) -> Result<Changelog> {
+    let project = Arc::new(project_name);
+    let mut changelog = Changelog::default();
+    let mut handles: JoinSet<ChunkResult> = JoinSet::new();
+
+    for chunk in versions.chunks(RELEASES_PER_QUERY) {
+        if handles.len() >= MAX_CONCURRENT_QUERIES {
+            if let Some(joined) = handles.join_next().await {
+                collect(&mut changelog, &release_prefix, joined);
+            }
+        }
+
+        let client = client.clone();
+        let project = Arc::clone(&project);
+        let chunk = chunk.to_vec();
+        let names: Vec<String> = chunk
+            .iter()
+            .map(|v| release_name(&release_prefix, &v.to_string()))
+            .collect();
+
+        handles.spawn(async move { (chunk, get_releases_tickets(&client, project, names).await) });
+    }
+
+    while let Some(joined) = handles.join_next().await {
+        collect(&mut changelog, &release_prefix, joined);
+    }
+
+    changelog.releases.sort_by(|a, b| {
+        let a = Version::parse(&a.version).ok();
+        let b = Version::parse(&b.version).ok();
+
+        b.cmp(&a)
+    });
+
+    Ok(changelog)
+}
+
+type ChunkResult = (Vec<Version>, Result<HashMap<String, Vec<Entry>>>);
+
+fn collect(
+    changelog: &mut Changelog,
+    release_prefix: &str,
+    joined: std::result::Result<ChunkResult, JoinError>,
+) {
+    let (versions, result) = match joined {
+        Ok(value) => value,
+        Err(e) => {
+            changelog.failures.push(ReleaseFailure {
+                version: "unknown".into(),
+                error: ApiError::GenericError(e.to_string()),
+            });
+            return;
+        }
+    };
+
+    match result {
+        Ok(mut tickets) => {
+            for version in versions {
+                let version = version.to_string();
+                let name = release_name(release_prefix, &version);
+
+                if let Some(entries) = tickets.remove(&name).filter(|e| !e.is_empty()) {
+                    changelog.releases.push(ReleaseLog { version, entries });
+                }
+            }
+        }
+        Err(error) => changelog
+            .failures
+            .extend(versions.into_iter().map(|v| ReleaseFailure {
+                version: v.to_string(),
+                error: error.clone(),
+            })),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReleaseItem {
+    id: usize,
+    name: String,
+    entity_type: EntityType,
+    release: Option<IdAndName>,
+}
+
+impl From<ReleaseItem> for Entry {
+    fn from(item: ReleaseItem) -> Self {
+        Self {
+            id: item.id,
+            name: item.name,
+            entity_type: item.entity_type.name,
+        }
+    }
+}
+
+/// Tickets of multiple releases in a single request, grouped by release name
+async fn get_releases_tickets(
+    client: &TargetProcessClient,
+    project: Arc<String>,
+    release_names: Vec<String>,
+) -> Result<HashMap<String, Vec<Entry>>> {
+    let names = release_names
+        .iter()
+        .map(|n| format!("'{n}'"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let filter = format!("(Project.Name='{project}')and(Release.Name in [{names}])");
+
+    // the releases share the pages, walk all of them instead of truncating big ranges
+    let items = client
+        .fetch_all::<ResponseListV2<ReleaseItem>>(
+            "/v2/assignables",
+            vec![
+                Param::Where(filter),
+                Param::Select("{id,name,entityType,release}".into()),
+            ],
+        )
+        .await?;
+
+    let mut tickets: HashMap<String, Vec<Entry>> = HashMap::new();
+
+    for item in items {
+        if let Some(release) = item.release.clone() {
+            tickets
+                .entry(release.name)
+                .or_default()
+                .push(Entry::from(item));
+        }
+    }
+
+    Ok(tickets)
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::*;
+
+    #[test]
+    fn parse_release_names() {
+        assert_eq!(
+            parse_release_name("web@2.10.1", "web"),
+            Some(Version::new(2, 10, 1))
+        );
+        assert_eq!(
+            parse_release_name("v1.2.3", ""),
+            Some(Version::new(1, 2, 3))
+        );
+        assert_eq!(parse_release_name("api@2.0.1", "web"), None);
+        assert_eq!(parse_release_name("api@2.0.1", ""), None);
+        assert_eq!(parse_release_name("2.0.1", ""), Some(Version::new(2, 0, 1)));
+        assert_eq!(parse_release_name("latest", ""), None);
+    }
+
+    #[test]
+    fn version_bounds() {
+        assert_eq!(
+            parse_version_bound("1.4.0", false),
+            Some(Version::new(1, 4, 0))
+        );
+        assert_eq!(
+            parse_version_bound("1.4", false),
+            Some(Version::new(1, 4, 0))
+        );
+        assert_eq!(
+            parse_version_bound("2", true),
+            Some(Version::new(2, u64::MAX, u64::MAX))
+        );
+        assert_eq!(parse_version_bound("abc", true), None);
+    }
+
+    #[test]
+    fn filter_version_range() {
+        let versions = vec![
+            Version::new(1, 3, 9),
+            Version::new(1, 4, 0),
+            Version::new(1, 4, 12),
+            Version::new(2, 1, 3),
+            Version::new(2, 1, 4),
+            Version::new(1, 4, 12),
+        ];
+
+        let from = Version::new(1, 4, 0);
+        let to = Version::new(2, 1, 3);
+
+        assert_eq!(
+            filter_versions(versions, Some(&from), Some(&to)),
+            vec![
+                Version::new(2, 1, 3),
+                Version::new(1, 4, 12),
+                Version::new(1, 4, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_chunks() {
+        let mut changelog = Changelog::default();
+        let entry = Entry {
+            id: 1,
+            name: "Add login".into(),
+            entity_type: "UserStory".into(),
+        };
+
+        let tickets = HashMap::from([("web@1.1.0".to_string(), vec![entry.clone()])]);
+        let versions = vec![Version::new(1, 1, 0), Version::new(1, 0, 0)];
+        collect(&mut changelog, "web", Ok((versions, Ok(tickets))));
+
+        let versions = vec![Version::new(2, 0, 0)];
+        let error = ApiError::GenericError("timeout".into());
+        collect(&mut changelog, "web", Ok((versions, Err(error))));
+
+        assert_eq!(
+            changelog.releases,
+            vec![ReleaseLog {
+                version: "1.1.0".into(),
+                entries: vec![entry]
+            }]
+        );
+        assert_eq!(changelog.failures.len(), 1);
+        assert_eq!(changelog.failures[0].version, "2.0.0");
+    }
+}
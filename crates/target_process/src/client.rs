@@ -0,0 +1,223 @@
+use reqwest::{header::*, multipart::Form, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{errors::ApiError, Page, Param, Result};
+
+pub const TOKEN_ENV_NAME: &str = "TARGET_PROCESS_ACCESS_TOKEN";
+
+/// Items per request when walking a whole list
+const PAGE_SIZE: usize = 1000;
+
+/// Connection to a TargetProcess instance, passed to every api function
+#[derive(Debug, Clone)]
+pub struct TargetProcessClient {
+    base_url: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl TargetProcessClient {
+    /// `base_url` is the instance root, e.g. `https://my-company.tpondemand.com`
+    pub fn new(base_url: &str, token: &str) -> Self {
+        Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            token: token.trim().to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn has_token(&self) -> bool {
+        !self.token.is_empty()
+    }
+
+    /// Web page of an entity
+    pub fn entity_url(&self, id: usize) -> String {
+        format!("{}/entity/{id}", self.base_url)
+    }
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+
+        headers
+    }
+
+    /// `path` relative to `/api`, or to the instance root with `root`
+    fn url<I>(&self, path: &str, params: I, root: bool) -> Result<reqwest::Url>
+    where
+        I: IntoIterator<Item = Param>,
+    {
+        let mut all: Vec<Param> = vec![Param::AccessToken(self.token.clone())];
+        all.extend(params);
+
+        let params: Vec<(String, String)> = all.into_iter().map(|p| p.into()).collect();
+
+        let base = match root {
+            true => self.base_url.clone(),
+            false => format!("{}/api", self.base_url),
+        };
+
+        reqwest::Url::parse_with_params(&format!("{base}/{}", path.trim_start_matches('/')), params)
+            .map_err(|_| ApiError::UrlParsing)
+    }
+
+    async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T> {
+        let response = request
+            .headers(Self::headers())
+            .send()
+            .await
+            .map_err(|e| ApiError::GenericError(e.to_string()))?;
+
+        if response.status() >= StatusCode::BAD_REQUEST {
+            return Err(ApiError::HTTP(response.status()));
+        }
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| ApiError::Json(e.to_string()))
+    }
+
+    pub async fn fetch<T, I>(&self, path: &str, params: I) -> Result<T>
+    where
+        I: IntoIterator<Item = Param>,
+        T: DeserializeOwned,
+    {
+        let url = self.url(path, params, false)?;
+
+        if cfg!(debug_assertions) {
+            println!("GET {url}");
+        }
+
+        Self::send(self.http.get(url)).await
+    }
+
+    /// Every item of a list, requested `PAGE_SIZE` at a time instead of stopping at the
+    /// first page
+    pub(crate) async fn fetch_all<P: Page>(
+        &self,
+        path: &str,
+        params: Vec<Param>,
+    ) -> Result<Vec<P::Item>> {
+        let mut items = vec![];
+
+        loop {
+            let mut page_params = params.clone();
+            page_params.extend([Param::Take(PAGE_SIZE), Param::Skip(items.len())]);
+
+            let page = self.fetch::<P, _>(path, page_params).await?.into_items();
+            let last = page.len() < PAGE_SIZE;
+            items.extend(page);
+
+            if last {
+                return Ok(items);
+            }
+        }
+    }
+
+    pub async fn post<T: DeserializeOwned, P: Serialize>(
+        &self,
+        path: &str,
+        payload: P,
+    ) -> Result<T> {
+        let url = self.url(path, [], false)?;
+
+        Self::send(self.http.post(url).json(&payload)).await
+    }
+
+    /// Sends a `multipart/form-data` request, `path` is relative to the instance root
+    pub async fn multipart<T: DeserializeOwned>(&self, path: &str, form: Form) -> Result<T> {
+        let url = self.url(path, [], true)?;
+
+        Self::send(self.http.post(url).multipart(form)).await
+    }
+
+    /// `url` with the access token, only for urls of the instance
+    fn authorized(&self, url: &str) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(url).map_err(|_| ApiError::UrlParsing)?;
+        let base = reqwest::Url::parse(&self.base_url).map_err(|_| ApiError::UrlParsing)?;
+
+        if url.origin() != base.origin() {
+            return Err(ApiError::ForeignUrl(url.origin().ascii_serialization()));
+        }
+
+        url.query_pairs_mut()
+            .append_pair("access_token", &self.token);
+
+        Ok(url)
+    }
+
+    /// Downloads raw bytes from an absolute url of the instance (e.g. an attachment `Uri`)
+    pub async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let url = self.authorized(url)?;
+
+        let response = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ApiError::GenericError(e.to_string()))?;
+
+        if response.status() >= StatusCode::BAD_REQUEST {
+            return Err(ApiError::HTTP(response.status()));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ApiError::GenericError(e.to_string()))?;
+
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn urls() {
+        let client = TargetProcessClient::new("https://acme.tpondemand.com/", "token");
+
+        assert_eq!(
+            client
+                .url("/v1/Assignables/42", [Param::Take(1)], false)
+                .unwrap()
+                .as_str(),
+            "https://acme.tpondemand.com/api/v1/Assignables/42?access_token=token&take=1"
+        );
+        assert_eq!(
+            client.url("/UploadFile.ashx", [], true).unwrap().as_str(),
+            "https://acme.tpondemand.com/UploadFile.ashx?access_token=token"
+        );
+        assert_eq!(
+            client.entity_url(42),
+            "https://acme.tpondemand.com/entity/42"
+        );
+    }
+
+    #[test]
+    fn token_stays_on_the_instance() {
+        let client = TargetProcessClient::new("https://acme.tpondemand.com", "token");
+
+        assert_eq!(
+            client
+                .authorized("https://acme.tpondemand.com/GetFile.aspx?AttachmentID=1")
+                .unwrap()
+                .as_str(),
+            "https://acme.tpondemand.com/GetFile.aspx?AttachmentID=1&access_token=token"
+        );
+        assert!(client.authorized("https://evil.com/GetFile.aspx").is_err());
+        assert!(client
+            .authorized("http://acme.tpondemand.com/GetFile.aspx")
+            .is_err());
+    }
+}
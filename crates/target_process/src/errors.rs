@@ -20,6 +20,12 @@ pub enum ApiError {
     #[error("Failed to parse url")]
     UrlParsing,
 
+    #[error("IO error: {0}")]
+    IO(String),
+
+    #[error("Refusing to send the access token outside of TargetProcess: {0}")]
+    ForeignUrl(String),
+
     #[error("Unable to extract token: {source}")]
     TokenNotFound {
         #[from]
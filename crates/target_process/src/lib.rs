@@ -1,53 +1,69 @@
-use std::sync::{Arc, Mutex};
+use std::path::Path;
 
 use cached::proc_macro::cached;
 
-use color_eyre::eyre::Context;
 use errors::*;
 use models::{
     user::CurrentUser,
-    v1::assignable::{Assignable, UpdateEntityStatePayload, ID},
-    v2::{self, assignable::Project as ProjectV2},
+    v1::assignable::{Assignable, Feature, GeneralUser, UpdateEntityStatePayload, ID},
+    v1::attachment::Attachment,
+    v1::release::{AssignReleasePayload, CreateReleasePayload, Release},
+    v2::{
+        self,
+        assignable::Project as ProjectV2,
+        hierarchy::{collection_for, select_for, HierarchyItem, TreeNode},
+        iteration::{Iteration, IterationAssignable, TeamIteration},
+    },
     EntityStates,
 };
-use reqwest::{header::*, StatusCode};
+use reqwest::multipart::{Form, Part};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::models::v1::assignable::Project;
 
+pub mod changelog;
+mod client;
 pub mod errors;
 pub mod models;
 
-type Result<T> = std::result::Result<T, ApiError>;
+pub use changelog::generate_changelog;
+pub use client::{TargetProcessClient, TOKEN_ENV_NAME};
+
+pub(crate) type Result<T> = std::result::Result<T, ApiError>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-struct ResponseListV1<T> {
+pub(crate) struct ResponseListV1<T> {
     pub items: Vec<T>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ResponseListV2<T> {
+pub(crate) struct ResponseListV2<T> {
     pub items: Vec<T>,
 }
 
-fn make_client() -> reqwest::Client {
-    reqwest::Client::new()
+/// One page of a list response, see [`TargetProcessClient::fetch_all`]
+pub(crate) trait Page: DeserializeOwned {
+    type Item;
+
+    fn into_items(self) -> Vec<Self::Item>;
 }
 
-fn get_headers() -> HeaderMap {
-    let mut headers = HeaderMap::new();
-    headers.insert(ACCEPT, "application/json".parse().unwrap());
+impl<T: DeserializeOwned> Page for ResponseListV1<T> {
+    type Item = T;
 
-    headers
+    fn into_items(self) -> Vec<T> {
+        self.items
+    }
 }
 
-pub fn get_token() -> Result<String> {
-    let token = std::env::var("TARGET_PROCESS_ACCESS_TOKEN")
-        .map_err(|source| ApiError::TokenNotFound { source })?;
+impl<T: DeserializeOwned> Page for ResponseListV2<T> {
+    type Item = T;
 
-    Ok(token)
+    fn into_items(self) -> Vec<T> {
+        self.items
+    }
 }
 
 pub trait Parameter {
@@ -56,44 +72,12 @@ pub trait Parameter {
 
 pub const ENV_NAME: &str = "TARGET_PROCESS_API_BASE_URL";
 
-pub fn is_configured() -> bool {
-    let value = std::env::var(ENV_NAME).ok();
-
-    value.is_some()
-}
-
-fn make_url<I>(path: String, _params: I) -> Result<reqwest::Url>
-where
-    I: IntoIterator<Item = Param>,
-{
-    let base = std::env::var(ENV_NAME)
-        .context("Unable to retrive `TARGET_PROCESS_API_BASE_URL` env variable.")
-        .unwrap();
-
-    let token = get_token()?;
-    let mut params: Vec<Param> = vec![Param::AccessToken(token)];
-    params.extend(_params);
-
-    let params: Vec<(String, String)> = params.into_iter().map(|p| p.into()).collect();
-
-    let base_url = format!("{base}/api");
-
-    let url =
-        reqwest::Url::parse_with_params(&format!("{base_url}/{path}").replace("//", "/"), params)
-            .map_err(|_| ApiError::UrlParsing)?;
-
-    Ok(url)
-}
-
-pub fn has_token() -> bool {
-    get_token().is_ok()
-}
-
 #[derive(Debug, Clone)]
 pub enum Param {
     Select(String),
     Where(String),
     Filter(String),
+    Include(String),
     AccessToken(String),
     Take(usize),
     Skip(usize),
@@ -106,6 +90,7 @@ impl Into<(String, String)> for Param {
             Self::AccessToken(value) => ("access_token".to_string(), value),
             Self::Where(value) => ("where".to_string(), value),
             Self::Filter(value) => ("filter".to_string(), value),
+            Self::Include(value) => ("include".to_string(), value),
             Self::Select(value) => ("select".to_string(), value),
             Self::Take(value) => ("take".to_string(), value.to_string()),
             Self::Skip(value) => ("skip".to_string(), value.to_string()),
@@ -119,296 +104,465 @@ impl From<(String, String)> for Param {
             "filter" => Self::Filter(value.1),
             "where" => Self::Where(value.1),
             "select" => Self::Select(value.1),
+            "include" => Self::Include(value.1),
             "access_token" => Self::AccessToken(value.1),
             _ => Self::Filter(value.1),
         }
     }
 }
 
-pub async fn post<T: DeserializeOwned, P: Serialize>(path: String, payload: P) -> Result<T> {
-    let client = make_client();
-    let headers = get_headers();
-    let url = make_url(path, [])?;
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssignablesList {
+    pub items: serde_json::Value,
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{ format!("{}/{id}", client.base_url()) }"#
+)]
+pub async fn get_project(client: &TargetProcessClient, id: String) -> Result<Project> {
+    let url = format!("/v1/Projects/{id}");
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| ApiError::GenericError(e.to_string()))?;
+    client.fetch(&url, []).await
+}
+
+#[cached(
+    key = "(String, usize, usize)",
+    convert = r#"{ (client.base_url().to_string(), skip, take) }"#
+)]
+pub async fn get_projects(
+    client: &TargetProcessClient,
+    skip: usize,
+    take: usize,
+) -> Result<Vec<ProjectV2>> {
+    let url = "/v2/projects";
+
+    let data: ResponseListV2<ProjectV2> = client
+        .fetch(
+            url,
+            [
+                Param::Take(take),
+                Param::Skip(skip),
+                Param::Select("{id,name,resourceType,abbreviation}".to_string()),
+            ],
+        )
+        .await?;
 
-    if response.status() >= StatusCode::BAD_REQUEST {
-        return Err(ApiError::HTTP(response.status()));
-    }
+    Ok(data.items)
+}
 
-    response
-        .json::<T>()
+#[cached(
+    key = "(String, String, String)",
+    convert = r#"{ (client.base_url().to_string(), filter.clone(), select.clone()) }"#
+)]
+pub async fn get_assignables(
+    client: &TargetProcessClient,
+    filter: String,
+    select: String,
+) -> Result<AssignablesList> {
+    let url = "/v2/assignables";
+
+    client
+        .fetch(url, [Param::Filter(filter), Param::Select(select)])
         .await
-        .map_err(|e| ApiError::Json(e.to_string()))
 }
 
-pub async fn fetch<T, I>(path: String, params: I) -> Result<T>
-where
-    I: IntoIterator<Item = Param> + Clone,
-    T: DeserializeOwned + Clone,
-{
-    let client = make_client();
-    let url = make_url(path, params)?;
-    let headers = get_headers();
+#[cached(
+    key = "String",
+    convert = r#"{ format!("{}/{id}", client.base_url()) }"#
+)]
+pub async fn get_assignable(client: &TargetProcessClient, id: String) -> Result<Assignable> {
+    let url = format!("/v1/Assignables/{id}");
+    let mut assignable: Assignable = client.fetch(&url, []).await?;
 
-    if cfg!(debug_assertions) {
-        println!("GET {url}");
-    }
+    let (assigned_users, feature) = tokio::join!(
+        get_assigned_users(client, id.clone()),
+        get_parent_feature(client, &assignable)
+    );
 
-    let response = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| ApiError::GenericError(e.to_string()))?;
+    // extra context only, the ticket is still usable without it
+    assignable.assigned_user = assigned_users.ok();
+    assignable.feature = feature.ok().flatten();
+
+    Ok(assignable)
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{ format!("{}/{id}", client.base_url()) }"#
+)]
+pub async fn get_assigned_users(
+    client: &TargetProcessClient,
+    id: String,
+) -> Result<Vec<GeneralUser>> {
+    let url = format!("/v1/Assignables/{id}/AssignedUser");
+    let data: ResponseListV1<GeneralUser> = client.fetch(&url, []).await?;
+
+    Ok(data.items)
+}
 
-    if response.status() >= StatusCode::BAD_REQUEST {
-        return Err(ApiError::HTTP(response.status()));
+/// `Feature` is only exposed by the UserStories/Bugs resources
+async fn get_parent_feature(
+    client: &TargetProcessClient,
+    assignable: &Assignable,
+) -> Result<Option<Feature>> {
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct WithFeature {
+        feature: Option<Feature>,
     }
 
-    response
-        .json::<T>()
-        .await
-        .map_err(|e| ApiError::Json(e.to_string()))
+    let resource = if assignable.is_user_story() {
+        "UserStories"
+    } else if assignable.is_bug() {
+        "Bugs"
+    } else {
+        return Ok(None);
+    };
 
-    // let text = response
-    //     .text()
-    //     .await
-    //     .map_err(|e| ApiError::GenericError(e.to_string()))?;
-    //
-    // serde_json::from_str(&text).map_err(|e| ApiError::Json(e.to_string()))
+    let url = format!("/v1/{resource}/{id}", id = assignable.id);
+    let data: WithFeature = client
+        .fetch(&url, [Param::Include("[Feature[Id,Name,Epic]]".into())])
+        .await?;
+
+    Ok(data.feature)
 }
 
-#[derive(Debug)]
-enum Row {
-    Title(String),
-    Log(String, String),
+#[cached(key = "String", convert = r#"{ client.base_url().to_string() }"#)]
+pub async fn get_me(client: &TargetProcessClient) -> Result<CurrentUser> {
+    client.fetch("/v1/Users/loggeduser", []).await
 }
 
-impl ToString for Row {
-    fn to_string(&self) -> String {
-        let base_url = get_base_url();
+pub async fn get_current_sprint_open_tasks(
+    client: &TargetProcessClient,
+    project_name: &str,
+) -> Result<Vec<Assignable>> {
+    let where_filter = Param::Where(format!(
+        r#"(EntityState.IsInitial = true) and ( EntityType.Name = 'Bug' or (TeamIteration.IsCurrent=true or TeamIteration.IsPrevious = true))and(Project.Name='{project_name}')"#
+    ));
 
-        match self {
-            Self::Title(version) => format!("## {version}"),
-            Self::Log(id, name) => format!("- [{id}]({base_url}/entity/{id}) {name}"),
-        }
-    }
+    let select_filter =
+        Param::Select("{id,name,description,resourceType,entityState,entityType}".into());
+
+    let api_response: ResponseListV2<v2::assignable::Assignable> = client
+        .fetch("/v2/assignables", vec![where_filter, select_filter])
+        .await?;
+
+    Ok(api_response
+        .items
+        .into_iter()
+        .map(Assignable::from)
+        .collect())
 }
 
-impl Row {
-    fn to_string_plain(&self) -> String {
-        match self {
-            Self::Title(version) => version.into(),
-            Self::Log(id, name) => {
-                let pattern = r#"^\"|\"$"#;
-                let re = regex::Regex::new(pattern).unwrap();
-                let name = re.replace_all(name, "");
+async fn get_hierarchy_items(
+    client: &TargetProcessClient,
+    resource_type: &str,
+    filter: String,
+) -> Result<Vec<HierarchyItem>> {
+    let url = format!("/v2/{}", collection_for(resource_type));
+
+    let data: ResponseListV2<HierarchyItem> = client
+        .fetch(
+            &url,
+            [
+                Param::Where(filter),
+                Param::Select(select_for(resource_type).to_string()),
+                Param::Take(1000),
+            ],
+        )
+        .await?;
 
-                format!("- [{id}] {}", name)
+    Ok(data.items)
+}
+
+async fn get_hierarchy_item(
+    client: &TargetProcessClient,
+    resource_type: &str,
+    id: usize,
+) -> Result<HierarchyItem> {
+    get_hierarchy_items(client, resource_type, format!("(id={id})"))
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ApiError::AssignableNotFound(id.to_string()))
+}
+
+/// Builds the Epic → Feature → UserStory → Tasks/Bugs tree around the given entity.
+/// Siblings are listed without their own children, only the branch of `id` is expanded.
+#[cached(
+    key = "(String, usize)",
+    convert = r#"{ (client.base_url().to_string(), id) }"#
+)]
+pub async fn get_ticket_tree(client: &TargetProcessClient, id: usize) -> Result<TreeNode> {
+    let current = get_hierarchy_item(client, "Assignable", id).await?;
+    let current = get_hierarchy_item(client, &current.resource_type, id).await?;
+
+    let mut story: Option<HierarchyItem> = None;
+    let mut node = TreeNode::leaf(current.clone());
+
+    match current.resource_type.as_str() {
+        "Task" | "Bug" => {
+            if let Some(parent) = &current.user_story {
+                story = Some(get_hierarchy_item(client, "UserStory", parent.id).await?);
             }
         }
+        "UserStory" => story = Some(current.clone()),
+        _ => {}
     }
-}
 
-#[cached]
-pub async fn generate_changelog(
-    from: usize,
-    to: Option<usize>,
-    project_name: String,
-    release_prefix: String,
-    plain: bool,
-    no_title: bool,
-) -> Result<Vec<String>> {
-    let to = to.unwrap_or(from);
-
-    let prefix = Arc::new(release_prefix);
-    let project = Arc::new(project_name);
-    let range = (from..to + 1).rev();
-    let rows: Arc<Mutex<Vec<Row>>> = Arc::new(Mutex::new(Vec::new()));
-
-    for minor in range {
-        let rows = Arc::clone(&rows);
-        let project = Arc::clone(&project);
-        let prefix = Arc::clone(&prefix);
-        let prefix = Arc::clone(&prefix);
-
-        tokio::spawn(async move {
-            let version = Arc::new(format!("1.{minor}"));
-
-            for patch in 0..10 {
-                // let rows = Arc::clone(&rows);
-                let project = Arc::clone(&project);
-                let prefix = Arc::clone(&prefix);
-                let version = Arc::clone(&version);
-
-                let version = format!("{version}.{patch}");
-
-                let elements = get_tag_tickets(project, prefix, version.clone())
-                    .await
-                    .unwrap();
-
-                if elements.is_empty() {
-                    return;
-                }
-
-                if let Ok(mut v) = rows.lock() {
-                    let row = Row::Title(version);
-                    v.push(row);
-
-                    for (id, name) in elements {
-                        let row = Row::Log(id, name);
-                        v.push(row);
-                    }
-                }
-            }
-        })
-        .await
-        .map_err(|e| ApiError::GenericError(e.to_string()))?;
+    if let Some(story) = story {
+        let filter = format!("(userStory.id={})", story.id);
+        let (tasks, bugs) = tokio::join!(
+            get_hierarchy_items(client, "Task", filter.clone()),
+            get_hierarchy_items(client, "Bug", filter)
+        );
+
+        let children = tasks?
+            .into_iter()
+            .chain(bugs?)
+            .map(TreeNode::leaf)
+            .collect();
+
+        node = TreeNode::new(story, children);
     }
 
-    let rows = rows.lock().unwrap();
-    let strings: Vec<String> = rows
-        .iter()
-        .filter_map(|row| {
-            if no_title && matches!(row, Row::Title(_)) {
-                return None;
-            }
+    let feature_id = node.item.feature.as_ref().map(|f| f.id);
 
-            Some(match plain {
-                true => row.to_string_plain(),
-                _ => row.to_string(),
-            })
-        })
-        .collect();
+    if let Some(feature_id) = feature_id {
+        let feature = get_hierarchy_item(client, "Feature", feature_id).await?;
+        let stories =
+            get_hierarchy_items(client, "UserStory", format!("(feature.id={feature_id})")).await?;
+
+        let mut feature_node =
+            TreeNode::new(feature, stories.into_iter().map(TreeNode::leaf).collect());
+        feature_node.replace_child(node);
+
+        node = feature_node;
+    } else if node.item.resource_type == "Feature" {
+        let stories =
+            get_hierarchy_items(client, "UserStory", format!("(feature.id={id})")).await?;
+        node.children = stories.into_iter().map(TreeNode::leaf).collect();
+    }
 
-    Ok(strings)
+    let epic_id = node.item.epic.as_ref().map(|e| e.id);
+
+    if let Some(epic_id) = epic_id {
+        let epic = get_hierarchy_item(client, "Epic", epic_id).await?;
+        let features =
+            get_hierarchy_items(client, "Feature", format!("(epic.id={epic_id})")).await?;
+
+        let mut epic_node = TreeNode::new(epic, features.into_iter().map(TreeNode::leaf).collect());
+        epic_node.replace_child(node);
+
+        node = epic_node;
+    } else if node.item.resource_type == "Epic" {
+        let features = get_hierarchy_items(client, "Feature", format!("(epic.id={id})")).await?;
+        node.children = features.into_iter().map(TreeNode::leaf).collect();
+    }
+
+    Ok(node)
 }
 
-#[cached]
-async fn get_tag_tickets(
-    project: Arc<String>,
-    release_prefix: Arc<String>,
-    version: String,
-) -> Result<Vec<(String, String)>> {
-    let mut data = vec![];
-    let release_name = if release_prefix.is_empty() {
-        version.to_string()
-    } else {
-        format!("{release_prefix}@{version}")
-    };
+pub async fn get_attachments(client: &TargetProcessClient, id: String) -> Result<Vec<Attachment>> {
+    let url = format!("/v1/Assignables/{id}/Attachments");
+    let data: ResponseListV1<Attachment> = client.fetch(&url, []).await?;
 
-    let filter = format!("(Project.Name='{project}')and(Release.Name='{release_name}')");
-
-    let client = reqwest::Client::new();
-    let response = client
-        .get(format!("{}/api/v2/assignables", get_base_url()))
-        .header("Accept", "application/json")
-        .query(&[
-            ("access_token", get_token()?),
-            ("where", filter),
-            ("select", "{id,name}".to_string()),
-        ])
-        .send()
-        .await
-        .map_err(|e| ApiError::GenericError(e.to_string()))?;
+    Ok(data.items)
+}
 
-    let json: serde_json::Value = response
-        .json()
+pub async fn upload_attachment(
+    client: &TargetProcessClient,
+    general_id: usize,
+    file: &Path,
+) -> Result<Attachment> {
+    let content = tokio::fs::read(file)
         .await
-        .map_err(|e| ApiError::Json(e.to_string()))?;
-
-    if let Some(response) = json.as_object() {
-        if let Some(v) = response.get("items") {
-            if let Some(items) = v.as_array() {
-                for item in items {
-                    if let Some(item_obj) = item.as_object() {
-                        let id = item_obj.get("id").unwrap();
-                        let name = item_obj.get("name").unwrap();
-
-                        data.push((id.to_string(), name.to_string()));
-                    }
-                }
-            }
-        }
-    }
+        .map_err(|e| ApiError::IO(e.to_string()))?;
+
+    let file_name = file
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .ok_or_else(|| ApiError::IO(format!("invalid file path: {}", file.display())))?;
+
+    let form = Form::new()
+        .text("generalId", general_id.to_string())
+        .part("attachment", Part::bytes(content).file_name(file_name));
 
-    Ok(data)
+    client.multipart("/UploadFile.ashx", form).await
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct AssignablesList {
-    pub items: serde_json::Value,
+/// Current TeamIterations of the given project (one per team) along with their assignables
+pub async fn get_current_iterations(
+    client: &TargetProcessClient,
+    project_name: &str,
+) -> Result<Vec<Iteration>> {
+    let where_filter = Param::Where(format!(
+        "(TeamIteration.IsCurrent=true)and(Project.Name='{project_name}')"
+    ));
+
+    let select_filter = Param::Select(
+        "{id,name,resourceType,entityState,teamIteration,effort,effortCompleted,effortToDo,assignedUsers:assignedUser.select({id,fullName})}".into(),
+    );
+
+    let assignables = client
+        .fetch_all::<ResponseListV2<IterationAssignable>>(
+            "/v2/assignables",
+            vec![where_filter, select_filter],
+        )
+        .await?;
+
+    let mut ids: Vec<usize> = assignables
+        .iter()
+        .filter_map(|a| a.team_iteration.as_ref().map(|t| t.id))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let list = ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let team_iterations: ResponseListV2<TeamIteration> = client
+        .fetch(
+            "/v2/teamiterations",
+            [
+                Param::Where(format!("(id in [{list}])")),
+                Param::Select("{id,name,startDate,endDate,velocity}".into()),
+            ],
+        )
+        .await?;
+
+    let mut iterations: Vec<Iteration> = team_iterations
+        .items
+        .into_iter()
+        .map(|team_iteration| Iteration {
+            assignables: assignables
+                .iter()
+                .filter(|a| a.team_iteration.as_ref().map(|t| t.id) == Some(team_iteration.id))
+                .cloned()
+                .collect(),
+            team_iteration,
+        })
+        .collect();
+
+    iterations.sort_by(|a, b| a.team_iteration.name.cmp(&b.team_iteration.name));
+
+    Ok(iterations)
 }
 
-#[cached]
-pub async fn get_project(id: String) -> Result<Project> {
-    let url = format!("/v1/Projects/{id}");
+/// TargetProcess release name for a version, e.g. `web@1.4.0`
+pub fn release_name(prefix: &str, version: &str) -> String {
+    if prefix.is_empty() {
+        return version.to_string();
+    }
 
-    fetch(url, []).await
+    format!("{prefix}@{version}")
 }
 
-#[cached]
-pub async fn get_projects(skip: usize, take: usize) -> Result<Vec<ProjectV2>> {
-    let url = "/v2/projects".to_string();
+pub async fn get_release(
+    client: &TargetProcessClient,
+    project_name: &str,
+    name: &str,
+) -> Result<Option<Release>> {
+    let filter = format!("(Name eq '{name}')and(Project.Name eq '{project_name}')");
 
-    let data: ResponseListV2<ProjectV2> = fetch(
-        url,
-        [
-            Param::Take(take),
-            Param::Skip(skip),
-            Param::Select("{id,name,resourceType,abbreviation}".to_string()),
-        ],
-    )
-    .await?;
+    let data: ResponseListV1<Release> =
+        client.fetch("/v1/Releases", [Param::Where(filter)]).await?;
 
-    Ok(data.items)
+    Ok(data.items.into_iter().next())
 }
 
-#[cached]
-pub async fn get_assignables(filter: String, select: String) -> Result<AssignablesList> {
-    let url = String::from("/v2/assignables");
+/// Releases of a project, optionally only the ones named `<prefix>@...`
+pub async fn get_releases(
+    client: &TargetProcessClient,
+    project_name: &str,
+    release_prefix: &str,
+) -> Result<Vec<Release>> {
+    let mut filter = format!("(Project.Name eq '{project_name}')");
 
-    fetch(url, [Param::Filter(filter), Param::Select(select)]).await
+    if !release_prefix.is_empty() {
+        filter += &format!("and(Name contains '{release_prefix}@')");
+    }
+
+    client
+        .fetch_all::<ResponseListV1<Release>>("/v1/Releases", vec![Param::Where(filter)])
+        .await
 }
 
-#[cached]
-pub async fn get_assignable(id: String) -> Result<Assignable> {
-    let url = format!("/v1/Assignables/{id}");
+pub async fn create_release(
+    client: &TargetProcessClient,
+    project_name: &str,
+    name: &str,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> Result<Release> {
+    let project = search_project(client, project_name.to_string(), SearchOperator::Eq)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ApiError::GenericError(format!(
+            "Project `{project_name}` not found"
+        )))?;
+
+    let payload = CreateReleasePayload {
+        name: name.to_string(),
+        project: ID { id: project.id },
+        start_date: start_date.format("%Y-%m-%dT00:00:00").to_string(),
+        end_date: end_date.format("%Y-%m-%dT00:00:00").to_string(),
+    };
 
-    fetch(url, []).await
+    client.post("/v1/Releases", payload).await
 }
 
-#[cached]
-pub async fn get_me() -> Result<CurrentUser> {
-    fetch("/v1/Users/loggeduser".into(), []).await
+pub async fn assign_release(
+    client: &TargetProcessClient,
+    assignable_id: usize,
+    release_id: usize,
+) -> Result<Assignable> {
+    let payload = AssignReleasePayload {
+        id: assignable_id,
+        release: ID { id: release_id },
+    };
+
+    client
+        .post(&format!("/v1/Assignables/{assignable_id}"), payload)
+        .await
 }
 
-pub async fn get_current_sprint_open_tasks(project_name: &str) -> Result<Vec<Assignable>> {
+pub async fn get_release_assignables(
+    client: &TargetProcessClient,
+    project_name: &str,
+    name: &str,
+) -> Result<Vec<Assignable>> {
     let where_filter = Param::Where(format!(
-        r#"(EntityState.IsInitial = true) and ( EntityType.Name = 'Bug' or (TeamIteration.IsCurrent=true or TeamIteration.IsPrevious = true))and(Project.Name='{project_name}')"#
+        "(Project.Name='{project_name}')and(Release.Name='{name}')"
     ));
 
     let select_filter =
         Param::Select("{id,name,description,resourceType,entityState,entityType}".into());
 
-    let api_response: ResponseListV2<v2::assignable::Assignable> =
-        fetch("/v2/assignables".into(), vec![where_filter, select_filter]).await?;
+    let assignables = client
+        .fetch_all::<ResponseListV2<v2::assignable::Assignable>>(
+            "/v2/assignables",
+            vec![where_filter, select_filter],
+        )
+        .await?;
 
-    Ok(api_response
-        .items
-        .into_iter()
-        .map(Assignable::from)
-        .collect())
+    Ok(assignables.into_iter().map(Assignable::from).collect())
 }
 
-pub async fn assign_task(assignable_id: usize, user_id: usize) -> Result<Assignable> {
+pub async fn assign_task(
+    client: &TargetProcessClient,
+    assignable_id: usize,
+    user_id: usize,
+) -> Result<Assignable> {
     let payload = AssignDeveloperPayload {
         assignments: vec![AssignedUser {
             role: ID { id: 1 },
@@ -416,10 +570,13 @@ pub async fn assign_task(assignable_id: usize, user_id: usize) -> Result<Assigna
         }],
     };
 
-    post(format!("/v1/Assignables/{assignable_id}"), payload).await
+    client
+        .post(&format!("/v1/Assignables/{assignable_id}"), payload)
+        .await
 }
 
 pub async fn update_entity_state(
+    client: &TargetProcessClient,
     assignable_id: usize,
     entity_state_id: EntityStates,
 ) -> Result<Assignable> {
@@ -430,7 +587,9 @@ pub async fn update_entity_state(
         },
     };
 
-    post(format!("/v1/Assignables/{assignable_id}"), payload).await
+    client
+        .post(&format!("/v1/Assignables/{assignable_id}"), payload)
+        .await
 }
 
 #[derive(strum::Display, PartialEq, Eq, Clone, Hash)]
@@ -439,12 +598,19 @@ pub enum SearchOperator {
     Contains,
 }
 
-#[cached]
-pub async fn search_project(name: String, operator: SearchOperator) -> Result<Vec<Project>> {
+#[cached(
+    key = "(String, String, SearchOperator)",
+    convert = r#"{ (client.base_url().to_string(), name.clone(), operator.clone()) }"#
+)]
+pub async fn search_project(
+    client: &TargetProcessClient,
+    name: String,
+    operator: SearchOperator,
+) -> Result<Vec<Project>> {
     let filter = format!("Name {operator} '{name}'");
-    let url = "/v1/Projects".to_string();
+    let url = "/v1/Projects";
 
-    let data: ResponseListV1<Project> = fetch(url, [Param::Filter(filter)]).await?;
+    let data: ResponseListV1<Project> = client.fetch(url, [Param::Filter(filter)]).await?;
 
     Ok(data.items)
 }
@@ -461,7 +627,3 @@ pub(crate) struct AssignedUser {
     pub general_user: ID,
     pub role: ID,
 }
-
-pub fn get_base_url() -> String {
-    std::env::var(ENV_NAME).unwrap()
-}
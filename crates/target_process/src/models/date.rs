@@ -0,0 +1,56 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// Parses both the legacy `/Date(1715896800000+0200)/` format and ISO-8601 dates
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Some(raw) = value.strip_prefix("/Date(") {
+        let end = raw
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| matches!(c, '+' | '-' | ')'))
+            .map(|(i, _)| i)?;
+
+        let millis: i64 = raw[..end].parse().ok()?;
+
+        return DateTime::from_timestamp_millis(millis);
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .map(|d| d.and_utc())
+}
+
+pub(crate) fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+
+    Ok(value.as_deref().and_then(parse_date))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use super::parse_date;
+
+    #[test]
+    fn parse_legacy_date() {
+        let date = parse_date("/Date(1715896800000+0200)/").unwrap();
+
+        assert_eq!(date, Utc.timestamp_millis_opt(1715896800000).unwrap());
+    }
+
+    #[test]
+    fn parse_iso_date() {
+        let date = parse_date("2024-05-17T00:00:00").unwrap();
+
+        assert_eq!(date, Utc.with_ymd_and_hms(2024, 5, 17, 0, 0, 0).unwrap());
+        assert!(parse_date("not a date").is_none());
+    }
+}
@@ -4,6 +4,7 @@ pub mod v1;
 pub mod v2;
 
 pub mod assignable;
+pub mod date;
 pub mod user;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -1,8 +1,5 @@
 use super::models::*;
-use crate::{
-    get_base_url,
-    models::{v1::assignable::Project, v2::assignable::Assignable as AssignableV2},
-};
+use crate::models::{v1::assignable::Project, v2::assignable::Assignable as AssignableV2};
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -17,15 +14,32 @@ pub struct Assignable {
 
     /// Project?
     pub project: Option<Project>,
-}
 
-impl Assignable {
-    pub fn get_link(self) -> String {
-        let target_process_url = get_base_url();
+    #[serde(default)]
+    pub assigned_user: Option<Vec<GeneralUser>>,
 
-        format!("{target_process_url}/entity/{id}", id = self.id).to_string()
-    }
+    pub effort: Option<f64>,
+    pub effort_completed: Option<f64>,
+    pub effort_to_do: Option<f64>,
+    pub time_spent: Option<f64>,
+    pub time_remain: Option<f64>,
+
+    pub team_iteration: Option<TeamIteration>,
+    pub release: Option<Release>,
 
+    /// UserStory/Bug parent, not returned by `/Assignables`
+    #[serde(default)]
+    pub feature: Option<Feature>,
+
+    /// Comma separated list
+    pub tags: Option<String>,
+    pub priority: Option<Priority>,
+
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomField>>,
+}
+
+impl Assignable {
     pub fn get_branch(self) -> String {
         let mut name = self.name.clone().to_lowercase();
         name.retain(|x| {
@@ -45,6 +59,25 @@ impl Assignable {
     pub fn is_user_story(&self) -> bool {
         self.entity_type.name.to_lowercase() == "userstory"
     }
+
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tags
+            .as_ref()
+            .map(|tags| {
+                tags.split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_assigned_users(&self) -> Vec<String> {
+        self.assigned_user
+            .as_ref()
+            .map(|users| users.iter().map(|u| u.display_name()).collect())
+            .unwrap_or_default()
+    }
 }
 
 impl From<AssignableV2> for Assignable {
@@ -71,6 +104,18 @@ impl From<AssignableV2> for Assignable {
                 resource_type: p.resource_type,
                 name: p.name,
             }),
+            assigned_user: None,
+            effort: None,
+            effort_completed: None,
+            effort_to_do: None,
+            time_spent: None,
+            time_remain: None,
+            team_iteration: None,
+            release: None,
+            feature: None,
+            tags: None,
+            priority: None,
+            custom_fields: None,
         }
     }
 }
@@ -7,9 +7,20 @@ pub struct GeneralUser {
     pub first_name: String,
     pub last_name: String,
     pub login: String,
+    #[serde(default)]
     pub full_name: String,
 }
 
+impl GeneralUser {
+    pub fn display_name(&self) -> String {
+        if self.full_name.is_empty() {
+            return format!("{} {}", self.first_name, self.last_name);
+        }
+
+        self.full_name.clone()
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct IdAndName {
@@ -19,6 +30,46 @@ pub struct IdAndName {
 
 pub type EntityState = IdAndName;
 pub type EntityType = IdAndName;
+pub type TeamIteration = IdAndName;
+pub type Release = IdAndName;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Priority {
+    pub id: usize,
+    pub name: String,
+    pub importance: Option<usize>,
+}
+
+/// Feature an assignable belongs to, with its Epic when available
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Feature {
+    pub id: usize,
+    pub name: String,
+    pub epic: Option<IdAndName>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomField {
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub field_type: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
+
+impl CustomField {
+    /// Human readable value, `None` when the field is empty
+    pub fn display_value(&self) -> Option<String> {
+        match self.value.as_ref()? {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) if s.is_empty() => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+}
 
 // PAYLOAD
 
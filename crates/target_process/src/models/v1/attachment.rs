@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Attachment {
+    pub id: usize,
+    pub name: String,
+    pub description: Option<String>,
+
+    /// Size in bytes
+    pub size: Option<u64>,
+
+    /// Absolute download url (`GetFile.aspx?AttachmentID=...`)
+    pub uri: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl Attachment {
+    pub fn human_size(&self) -> String {
+        let Some(size) = self.size else {
+            return "-".into();
+        };
+
+        match size {
+            s if s >= 1024 * 1024 => format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)),
+            s if s >= 1024 => format!("{:.1} KB", s as f64 / 1024.0),
+            s => format!("{s} B"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Attachment;
+
+    #[test]
+    fn human_size() {
+        let mut attachment = Attachment {
+            id: 1,
+            name: "screenshot.png".into(),
+            description: None,
+            size: Some(512),
+            uri: None,
+            mime_type: None,
+        };
+
+        assert_eq!(attachment.human_size(), "512 B");
+
+        attachment.size = Some(2048);
+        assert_eq!(attachment.human_size(), "2.0 KB");
+
+        attachment.size = Some(3 * 1024 * 1024);
+        assert_eq!(attachment.human_size(), "3.0 MB");
+    }
+}
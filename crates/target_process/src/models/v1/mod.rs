@@ -1 +1,3 @@
 pub mod assignable;
+pub mod attachment;
+pub mod release;
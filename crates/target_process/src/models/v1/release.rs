@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::assignable::{Project, ID};
+use crate::models::date::deserialize_date;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Release {
+    pub id: usize,
+    pub name: String,
+
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    pub project: Option<Project>,
+}
+
+// PAYLOAD
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateReleasePayload {
+    pub name: String,
+    pub project: ID,
+
+    /// `YYYY-MM-DDTHH:MM:SS`
+    pub start_date: String,
+
+    /// `YYYY-MM-DDTHH:MM:SS`
+    pub end_date: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssignReleasePayload {
+    pub id: usize,
+    pub release: ID,
+}
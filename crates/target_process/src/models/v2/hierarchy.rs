@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use super::assignable::{EntityState, IdAndName};
+
+/// Generic entity returned by the v2 hierarchy queries (Epic, Feature, UserStory, Task, Bug)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HierarchyItem {
+    pub id: usize,
+    pub name: String,
+    pub resource_type: String,
+    pub entity_state: Option<EntityState>,
+
+    #[serde(default)]
+    pub user_story: Option<IdAndName>,
+
+    #[serde(default)]
+    pub feature: Option<IdAndName>,
+
+    #[serde(default)]
+    pub epic: Option<IdAndName>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeNode {
+    pub item: HierarchyItem,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn new(item: HierarchyItem, children: Vec<TreeNode>) -> Self {
+        Self { item, children }
+    }
+
+    pub fn leaf(item: HierarchyItem) -> Self {
+        Self::new(item, vec![])
+    }
+
+    pub fn id(&self) -> usize {
+        self.item.id
+    }
+
+    /// Replaces the child with the same id, or appends it when missing
+    pub fn replace_child(&mut self, node: TreeNode) {
+        match self.children.iter_mut().find(|c| c.id() == node.id()) {
+            Some(child) => *child = node,
+            None => self.children.push(node),
+        }
+    }
+}
+
+/// `select` clause for each resource, only requesting the parents it exposes
+pub(crate) fn select_for(resource_type: &str) -> &'static str {
+    match resource_type {
+        "Task" => "{id,name,resourceType,entityState,userStory}",
+        "Bug" => "{id,name,resourceType,entityState,userStory,feature}",
+        "UserStory" => "{id,name,resourceType,entityState,feature}",
+        "Feature" => "{id,name,resourceType,entityState,epic}",
+        _ => "{id,name,resourceType,entityState}",
+    }
+}
+
+/// v2 collection endpoint for a given resource type
+pub(crate) fn collection_for(resource_type: &str) -> &'static str {
+    match resource_type {
+        "Task" => "tasks",
+        "Bug" => "bugs",
+        "UserStory" => "userstories",
+        "Feature" => "features",
+        "Epic" => "epics",
+        _ => "assignables",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(id: usize) -> HierarchyItem {
+        HierarchyItem {
+            id,
+            name: format!("item {id}"),
+            resource_type: "UserStory".into(),
+            entity_state: None,
+            user_story: None,
+            feature: None,
+            epic: None,
+        }
+    }
+
+    #[test]
+    fn replace_child() {
+        let mut root = TreeNode::new(item(1), vec![TreeNode::leaf(item(2))]);
+
+        root.replace_child(TreeNode::new(item(2), vec![TreeNode::leaf(item(3))]));
+        root.replace_child(TreeNode::leaf(item(4)));
+
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].children.len(), 1);
+        assert_eq!(root.children[1].id(), 4);
+    }
+}
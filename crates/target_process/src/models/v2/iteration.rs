@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::assignable::{EntityState, IdAndName};
+use crate::models::date::deserialize_date;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamIteration {
+    pub id: usize,
+    pub name: String,
+
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    pub velocity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignedUser {
+    pub id: usize,
+    pub full_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationAssignable {
+    pub id: usize,
+    pub name: String,
+    pub resource_type: Option<String>,
+    pub entity_state: EntityState,
+    pub team_iteration: Option<IdAndName>,
+
+    pub effort: Option<f64>,
+    pub effort_completed: Option<f64>,
+    pub effort_to_do: Option<f64>,
+
+    #[serde(default)]
+    pub assigned_users: Vec<AssignedUser>,
+}
+
+impl IterationAssignable {
+    pub fn assignees(&self) -> String {
+        self.assigned_users
+            .iter()
+            .map(|u| u.full_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Iteration {
+    pub team_iteration: TeamIteration,
+    pub assignables: Vec<IterationAssignable>,
+}
+
+impl Iteration {
+    /// Days between `today` and the end of the iteration (negative once it's over)
+    pub fn days_left(&self, today: NaiveDate) -> Option<i64> {
+        let end = self.team_iteration.end_date?.date_naive();
+
+        Some((end - today).num_days())
+    }
+
+    pub fn total_days(&self) -> Option<i64> {
+        let start = self.team_iteration.start_date?.date_naive();
+        let end = self.team_iteration.end_date?.date_naive();
+
+        Some((end - start).num_days())
+    }
+
+    pub fn total_effort(&self) -> f64 {
+        self.assignables.iter().filter_map(|a| a.effort).sum()
+    }
+
+    pub fn completed_effort(&self) -> f64 {
+        self.assignables
+            .iter()
+            .filter_map(|a| a.effort_completed)
+            .sum()
+    }
+
+    pub fn remaining_effort(&self) -> f64 {
+        self.assignables.iter().filter_map(|a| a.effort_to_do).sum()
+    }
+
+    /// Remaining effort on a linear burndown from `start_date` to `end_date`
+    pub fn ideal_remaining_effort(&self, today: NaiveDate) -> Option<f64> {
+        let total_days = self.total_days()?;
+
+        // ends before it starts, bad data from the api
+        if total_days < 0 {
+            return None;
+        }
+
+        let days_left = self.days_left(today)?.clamp(0, total_days);
+
+        if total_days == 0 {
+            return Some(0.0);
+        }
+
+        Some(self.total_effort() * days_left as f64 / total_days as f64)
+    }
+
+    pub fn group_by_state(&self) -> BTreeMap<String, Vec<&IterationAssignable>> {
+        let mut groups: BTreeMap<String, Vec<&IterationAssignable>> = BTreeMap::new();
+
+        for assignable in &self.assignables {
+            groups
+                .entry(assignable.entity_state.name.clone())
+                .or_default()
+                .push(assignable);
+        }
+
+        groups
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+
+    fn assignable(state: &str, effort: f64, completed: f64) -> IterationAssignable {
+        IterationAssignable {
+            id: 1,
+            name: "story".into(),
+            resource_type: Some("UserStory".into()),
+            entity_state: IdAndName {
+                id: 1,
+                name: state.into(),
+            },
+            team_iteration: None,
+            effort: Some(effort),
+            effort_completed: Some(completed),
+            effort_to_do: Some(effort - completed),
+            assigned_users: vec![],
+        }
+    }
+
+    #[test]
+    fn burndown() {
+        let iteration = Iteration {
+            team_iteration: TeamIteration {
+                id: 1,
+                name: "Sprint 1".into(),
+                start_date: Some(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()),
+                end_date: Some(Utc.with_ymd_and_hms(2024, 5, 11, 0, 0, 0).unwrap()),
+                velocity: None,
+            },
+            assignables: vec![
+                assignable("Open", 4.0, 0.0),
+                assignable("Done", 6.0, 6.0),
+                assignable("Open", 2.0, 1.0),
+            ],
+        };
+
+        let today = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+
+        assert_eq!(iteration.total_days(), Some(10));
+        assert_eq!(iteration.days_left(today), Some(5));
+        assert_eq!(iteration.total_effort(), 12.0);
+        assert_eq!(iteration.completed_effort(), 7.0);
+        assert_eq!(iteration.remaining_effort(), 5.0);
+        assert_eq!(iteration.ideal_remaining_effort(today), Some(6.0));
+        assert_eq!(iteration.group_by_state()["Open"].len(), 2);
+
+        let reversed = Iteration {
+            team_iteration: TeamIteration {
+                start_date: iteration.team_iteration.end_date,
+                end_date: iteration.team_iteration.start_date,
+                ..iteration.team_iteration.clone()
+            },
+            ..iteration
+        };
+
+        assert_eq!(reversed.ideal_remaining_effort(today), None);
+    }
+}
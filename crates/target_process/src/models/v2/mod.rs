@@ -1 +1,3 @@
 pub mod assignable;
+pub mod hierarchy;
+pub mod iteration;